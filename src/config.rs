@@ -1,8 +1,154 @@
 /// represents a single configuration for the conversion process
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Config {
+    /// options changing how requests are converted
+    pub conversion: ConversionOptions,
     /// destination http file
     pub dest_file: String,
+    /// postman environment files to be converted to RestClient environments
+    pub environment_files: Vec<String>,
+    /// what to do if the destination file already exists
+    pub overwrite: OverwritePolicy,
+    /// whether an item which can not be parsed fails the whole collection
+    pub parsing: ParsingMode,
+    /// vscode settings file where environments are recorded. If None, .vscode/settings.json next to the destination file, or inside the split directory, is used
+    pub settings_file: Option<String>,
     /// source postman file
     pub source_file: String,
+    /// directory receiving one http file per postman folder, in subdirectories mirroring the folders. When set, dest_file is not used
+    pub split_by_folder: Option<String>,
+    /// json file receiving the name given to each request, by postman item id
+    pub names_file: Option<String>,
+    /// directory where files sent as request bodies are looked for. If None, the directory of source_file is used
+    pub files_base_dir: Option<String>,
+    /// directory receiving a copy of every file sent as a request body. Http files then reference the copies
+    pub copy_assets: Option<String>,
+}
+
+/// represents a single configuration for upgrading an old postman collection to v 2.1.0
+#[derive(Debug, Default)]
+pub struct UpgradeConfig {
+    /// destination v 2.1.0 collection file
+    pub dest_file: String,
+    /// what to do if the destination file already exists
+    pub overwrite: OverwritePolicy,
+    /// source postman file, in any supported format
+    pub source_file: String,
+}
+
+/// represents a single configuration for auditing how much of a collection survives the conversion. Nothing is written
+#[derive(Debug, Default)]
+pub struct AuditConfig {
+    /// options changing how requests are converted
+    pub conversion: ConversionOptions,
+    /// whether an item which can not be parsed fails the whole audit
+    pub parsing: ParsingMode,
+    /// source postman file
+    pub source_file: String,
+}
+
+/// what should happen when the destination file already exists
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// replace the existing file
+    Overwrite,
+    /// refuse to touch the existing file and report an error instead
+    NoClobber,
+}
+
+/// we never destroy a file the user did not explicitly ask us to replace
+impl Default for OverwritePolicy {
+    fn default() -> Self {
+        Self::NoClobber
+    }
+}
+
+/// how strictly collections are parsed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsingMode {
+    /// any item which can not be parsed fails the whole collection
+    Strict,
+    /// items which can not be parsed are reported as errors, the rest of the collection is still converted
+    Lenient,
+}
+
+/// a broken collection is something the user should know about before anything is written
+impl Default for ParsingMode {
+    fn default() -> Self {
+        Self::Strict
+    }
+}
+
+/// options changing how a collection is converted, regardless of where it comes from and where it goes
+#[derive(Clone, Debug, Default)]
+pub struct ConversionOptions {
+    /// what to do when the raw url of a request disagrees with its structured parts
+    pub url_conflict: UrlConflictPolicy,
+    /// how request names are built from the names of postman items and their folders
+    pub naming: NamingStyle,
+    /// what to do with postman faker variables, like {{$randomFirstName}}, which RestClient can not generate
+    pub faker: FakerPolicy,
+    /// parse graphql queries and report the ones which are not valid. Invalid queries are converted anyway
+    pub validate_graphql: bool,
+    /// keep disabled headers, query parameters and body fields as comments, instead of leaving them out
+    pub keep_disabled: bool,
+    /// requests are written one file per postman folder. RestClient requests only reference responses of requests in their own file, so chaining stops at folders
+    pub one_file_per_folder: bool,
+}
+
+/// postman generates fake data for variables like {{$randomEmail}}. RestClient only knows a few random values, such as {{$guid}} and {{$randomInt}}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FakerPolicy {
+    /// leave the variable as it is and report it. RestClient does not resolve it, so it is sent as plain text until someone replaces it
+    Keep,
+    /// replace the variable with a fixed sample value, always the same one, and report it
+    Sample,
+}
+
+/// fixed values where random ones were expected break requests creating unique things, like users with unique emails, and that goes unnoticed
+impl Default for FakerPolicy {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+/// how RestClient request names are built. Whatever the style, names only have ascii letters, digits, _ and -, and are unique
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NamingStyle {
+    /// folder and request names joined by _, words of each one joined by -, case kept. E.g: Users_get-user
+    Original,
+    /// all words in lower case joined by -. E.g: users-get-user
+    Kebab,
+    /// all words in lower case joined by _. E.g: users_get_user
+    Snake,
+    /// all words joined in camel case, starting in lower case. E.g: usersGetUser
+    Camel,
+    /// only the request name, without folders, words joined by -. E.g: get-user
+    Leaf,
+}
+
+/// names stay as close as possible to what users see in postman
+impl Default for NamingStyle {
+    fn default() -> Self {
+        Self::Original
+    }
+}
+
+/// postman stores urls twice: as raw text and split into protocol, host, path and so on
+/// both should describe the same url, but hand edited or generated collections frequently disagree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UrlConflictPolicy {
+    /// use the raw url
+    PreferRaw,
+    /// use the url built from the structured parts
+    PreferStructured,
+    /// use the url built from the structured parts, which is what postman sends, and warn about the conflict
+    Warn,
+}
+
+/// conflicts are too frequent to stop a conversion, but too relevant to silently pick a side
+impl Default for UrlConflictPolicy {
+    fn default() -> Self {
+        Self::Warn
+    }
 }
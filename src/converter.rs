@@ -1,21 +1,38 @@
 //! converter from postman collection to RestClient format
 
-use crate::{error::PostresError, postman::v2_1_0::PostmanCollection, restclient::HttpRequests};
+use crate::{
+    config::ConversionOptions, error::PostresError, postman::v2_1_0::PostmanCollection,
+    report::Report, restclient::HttpRequests,
+};
 
+mod environment;
+mod folder_tree;
+mod naming;
 mod v2_1_0;
 
+pub(crate) use environment::convert_environments;
+pub(crate) use folder_tree::folder_tree;
+pub(crate) use naming::names_mapping;
+
 /// converts a postman collection to RestClient http format
+/// items which cannot be converted are left out and, like anything converted differently from what postman would send, recorded in the report
 // This delegates to a specialized module to convert v 2.1.0 collections because if we ever want to add other versions this public api can make the switch without affecting callers
-pub fn convert_to_http(collection: &PostmanCollection) -> Result<HttpRequests, PostresError> {
-    Ok(v2_1_0::convert_to_http(&collection))
+pub fn convert_to_http(
+    collection: &PostmanCollection,
+    options: &ConversionOptions,
+) -> Result<(HttpRequests, Report), PostresError> {
+    Ok(v2_1_0::convert_to_http(collection, options))
 }
 
 /// constructs a base name according to the base name and name informed
 /// used to form names of requests which are originally inside folders in postman collections.
 /// Because RestClient has no concept of folders, the request name of a RestClient request is composed from the name of the folder (s) and the name of the request
 fn make_base_name(basename: &str, name: &str) -> String {
-    let name = name.replace(" ", "-");
-    if basename.is_empty() {
+    // names end up in file variable names, which must also be valid RestClient identifiers
+    let name = naming::segment(name);
+    if name.is_empty() {
+        basename.to_string()
+    } else if basename.is_empty() {
         name
     } else {
         format!("{basename}_{name}")
@@ -0,0 +1,195 @@
+//! converts postman environments to RestClient environments
+
+use crate::{
+    diagnostics::{child_pointer, ItemOrigin},
+    postman::environment::PostmanEnvironment,
+    report::Report,
+    restclient,
+};
+
+/// name RestClient gives to the environment whose variables are visible in all other environments
+const SHARED_ENVIRONMENT: &str = "$shared";
+
+/// what is written instead of the value of a secret variable
+/// secrets should not end up in a settings file, which is very likely to be committed
+const SECRET_PLACEHOLDER: &str = "<secret: value not exported>";
+
+/// converts a list of postman environments to RestClient environments
+/// variables having the same value in all environments are moved to the $shared environment
+pub(crate) fn convert_environments(
+    environments: &[PostmanEnvironment],
+    report: &mut Report,
+) -> Vec<restclient::Environment> {
+    let mut converted: Vec<_> = environments
+        .iter()
+        .map(|e| convert_environment(e, report))
+        .collect();
+    // with a single environment every variable would be common to all, which tells nothing. Sharing only makes sense for two or more
+    if converted.len() < 2 {
+        return converted;
+    }
+    let shared: Vec<restclient::Variable> = converted[0]
+        .variables
+        .iter()
+        .filter(|v| v.value != SECRET_PLACEHOLDER)
+        .filter(|v| converted[1..].iter().all(|e| e.variables.contains(v)))
+        .cloned()
+        .collect();
+    if shared.is_empty() {
+        return converted;
+    }
+    for environment in converted.iter_mut() {
+        environment.variables.retain(|v| !shared.contains(v));
+    }
+    converted.insert(
+        0,
+        restclient::Environment {
+            name: SHARED_ENVIRONMENT.to_string(),
+            variables: shared,
+        },
+    );
+    converted
+}
+
+fn convert_environment(
+    environment: &PostmanEnvironment,
+    report: &mut Report,
+) -> restclient::Environment {
+    let variables = environment
+        .values
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| !matches!(v.enabled, Some(false)))
+        .map(|(i, v)| {
+            let value = if matches!(v.value_type.as_deref(), Some("secret")) {
+                report
+                    .for_item(ItemOrigin {
+                        path: format!("environment {}", environment.name),
+                        pointer: child_pointer("/values", i),
+                        location: None,
+                    })
+                    .warning(format!(
+                        "secret variable {} was not exported, set its value by hand",
+                        v.key
+                    ));
+                SECRET_PLACEHOLDER.to_string()
+            } else {
+                match &v.value {
+                    None | Some(serde_json::Value::Null) => String::new(),
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                }
+            };
+            restclient::Variable {
+                description: None,
+                enabled: true,
+                name: v.key.clone(),
+                value,
+            }
+        })
+        .collect();
+    restclient::Environment {
+        name: environment.name.clone(),
+        variables,
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::postman::environment::EnvironmentValue;
+
+    fn environment(name: &str, values: Vec<(&str, serde_json::Value)>) -> PostmanEnvironment {
+        PostmanEnvironment {
+            name: name.to_string(),
+            values: values
+                .into_iter()
+                .map(|(key, value)| EnvironmentValue {
+                    key: key.to_string(),
+                    value: Some(value),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn variable(name: &str, value: &str) -> restclient::Variable {
+        restclient::Variable {
+            description: None,
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn should_not_create_shared_environment_for_a_single_environment() {
+        let converted = convert_environments(
+            &[environment("dev", vec![("a", json!("1")), ("b", json!(2))])],
+            &mut Report::default(),
+        );
+        assert_eq!(
+            converted,
+            vec![restclient::Environment {
+                name: "dev".to_string(),
+                variables: vec![variable("a", "1"), variable("b", "2")],
+            }]
+        );
+    }
+
+    #[test]
+    fn should_move_values_common_to_all_environments_to_shared() {
+        let converted = convert_environments(
+            &[
+                environment(
+                    "dev",
+                    vec![("version", json!("v1")), ("host", json!("dev"))],
+                ),
+                environment(
+                    "prod",
+                    vec![("host", json!("prod")), ("version", json!("v1"))],
+                ),
+            ],
+            &mut Report::default(),
+        );
+        assert_eq!(
+            converted,
+            vec![
+                restclient::Environment {
+                    name: "$shared".to_string(),
+                    variables: vec![variable("version", "v1")],
+                },
+                restclient::Environment {
+                    name: "dev".to_string(),
+                    variables: vec![variable("host", "dev")],
+                },
+                restclient::Environment {
+                    name: "prod".to_string(),
+                    variables: vec![variable("host", "prod")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_flag_secrets_and_skip_disabled_values() {
+        let mut dev = environment("dev", vec![("token", json!("abc")), ("old", json!("x"))]);
+        dev.values[0].value_type = Some("secret".to_string());
+        dev.values[1].enabled = Some(false);
+        let mut report = Report::default();
+        let converted = convert_environments(&[dev], &mut report);
+        assert_eq!(
+            converted[0].variables,
+            vec![variable("token", SECRET_PLACEHOLDER)]
+        );
+        assert_eq!(report.warnings(), 1);
+        assert_eq!(report.entries[0].diagnostic.origin.path, "environment dev");
+        assert_eq!(report.entries[0].diagnostic.origin.pointer, "/values/0");
+    }
+}
@@ -0,0 +1,127 @@
+//! rebuilds the postman folder structure around converted requests, so that they can be written one http file per folder
+
+use std::collections::HashMap;
+
+use crate::{
+    postman::{
+        item_name,
+        v2_1_0::{Items, PostmanCollection},
+    },
+    restclient::{HttpFolder, HttpRequest},
+    tree::Node,
+};
+
+/// a tree with the collection at the root and a node per postman folder, each one holding the requests found directly in it
+/// requests keep the order they were converted in. Folders with no request are still in the tree, their subfolders might have some
+pub(crate) fn folder_tree(
+    collection: &PostmanCollection,
+    requests: Vec<HttpRequest>,
+) -> Node<HttpFolder> {
+    let mut by_folder: HashMap<Vec<String>, Vec<HttpRequest>> = HashMap::new();
+    for r in requests {
+        by_folder
+            .entry(r.source().folder.clone())
+            .or_default()
+            .push(r);
+    }
+    build_node(
+        &collection.info.name,
+        &collection.item,
+        &mut vec![],
+        &mut by_folder,
+    )
+}
+
+/// path holds the names of the folder and of the ones enclosing it, from the outermost
+/// sibling folders with the same name can not be told apart by path: the first one takes the requests of both
+fn build_node(
+    name: &str,
+    items: &[Items],
+    path: &mut Vec<String>,
+    by_folder: &mut HashMap<Vec<String>, Vec<HttpRequest>>,
+) -> Node<HttpFolder> {
+    let node = Node::create_leaf(HttpFolder {
+        name: name.to_string(),
+        requests: by_folder.remove(path).unwrap_or_default(),
+    });
+    for (i, item) in items.iter().enumerate() {
+        // requests are leaves of the postman tree, they were already taken by their folder
+        let children = match &item.item {
+            Some(children) if !children.is_empty() => children,
+            _ => continue,
+        };
+        // nameless folders are named as the converter named them in the path of their requests
+        let name = item_name(item, i);
+        path.push(name.clone());
+        node.add_node(build_node(&name, children, path, by_folder));
+        path.pop();
+    }
+    node
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::ConversionOptions;
+
+    #[test]
+    fn should_put_requests_in_the_folders_they_were_found_in() {
+        let collection: PostmanCollection = serde_json::from_value(json!({
+            "info": { "name": "c", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+            "item": [
+                { "name": "Users", "item": [
+                    { "name": "list", "request": "http://host/users" },
+                    { "name": "Admin", "item": [
+                        { "name": "grant", "request": "http://host/grant" }
+                    ] },
+                    { "name": "get", "request": "http://host/users/1" }
+                ] },
+                { "name": "health", "request": "http://host/health" }
+            ]
+        }))
+        .unwrap();
+        let (requests, _) =
+            super::super::convert_to_http(&collection, &ConversionOptions::default()).unwrap();
+        let root = folder_tree(&collection, requests.requests);
+        let names = |node: &Node<HttpFolder>| {
+            let value = node.get_value();
+            let folder = value.as_ref().unwrap();
+            let requests: Vec<_> = folder
+                .requests
+                .iter()
+                .map(|r| r.to_restclient().lines().nth(1).unwrap().to_string())
+                .collect();
+            (folder.name.clone(), requests)
+        };
+        assert_eq!(
+            names(&root),
+            ("c".to_string(), vec!["# @name health".to_string()])
+        );
+        let users = root.get_first_child().unwrap();
+        assert_eq!(
+            names(&users),
+            (
+                "Users".to_string(),
+                vec![
+                    "# @name Users_list".to_string(),
+                    "# @name Users_get".to_string()
+                ]
+            )
+        );
+        let admin = users.get_first_child().unwrap();
+        assert_eq!(
+            names(&admin),
+            (
+                "Admin".to_string(),
+                vec!["# @name Users_Admin_grant".to_string()]
+            )
+        );
+        assert!(admin.is_leaf());
+        assert!(users.get_next().is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//! turns postman item names into RestClient request names
+//! Postman names are free text, RestClient only recognizes names made of ascii letters, digits, _ and -.
+//! Accented latin letters are transliterated, separators and punctuation split words and anything else, like emoji, is dropped.
+//! Names are made unique by suffixing repeated ones with -2, -3 ... in the order requests appear, so that converting the same collection twice gives the same names
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::{
+    config::NamingStyle,
+    error::PostresError,
+    restclient::{HttpRequest, RequestSource},
+};
+
+/// given to requests whose name has nothing usable
+const FALLBACK_NAME: &str = "request";
+
+/// renames every request according to the style, from the postman names it was converted from
+pub(crate) fn name_requests(requests: &mut [HttpRequest], style: NamingStyle) {
+    let names: Vec<_> = requests.iter().map(|r| name(r.source(), style)).collect();
+    // a plain name is never taken by a suffixed one, even if the plain one comes later
+    let mut used: HashSet<String> = names.iter().cloned().collect();
+    let mut seen = HashSet::new();
+    for (request, name) in requests.iter_mut().zip(names) {
+        let name = if seen.insert(name.clone()) {
+            name
+        } else {
+            (2..)
+                .map(|i| format!("{name}{}{i}", suffix_separator(style)))
+                .find(|n| used.insert(n.clone()))
+                .unwrap()
+        };
+        request.set_name(name);
+    }
+}
+
+/// a json list telling, for every request, the postman item it comes from and the name it was given
+/// items without id can still be found by their path
+pub(crate) fn names_mapping(requests: &[HttpRequest]) -> Result<String, PostresError> {
+    #[derive(Serialize)]
+    struct RequestName<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a str>,
+        path: String,
+        name: &'a str,
+    }
+    let names: Vec<_> = requests
+        .iter()
+        .map(|r| {
+            let source = r.source();
+            RequestName {
+                id: source.id.as_deref(),
+                path: [source.folder.as_slice(), std::slice::from_ref(&source.name)]
+                    .concat()
+                    .join("/"),
+                name: r.name(),
+            }
+        })
+        .collect();
+    serde_json::to_string_pretty(&names)
+        .map_err(|e| PostresError::DestFileError { msg: e.to_string() })
+}
+
+/// a single folder or request name in the original style: words joined by -, case kept
+pub(crate) fn segment(name: &str) -> String {
+    let mut segment = String::new();
+    for c in transliterate(name).chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+            segment.push(c);
+        } else if !segment.ends_with('-') {
+            segment.push('-');
+        }
+    }
+    segment.trim_matches('-').to_string()
+}
+
+fn name(source: &RequestSource, style: NamingStyle) -> String {
+    let parts = source.folder.iter().chain([&source.name]);
+    let name = match style {
+        NamingStyle::Original => parts
+            .map(|p| segment(p))
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("_"),
+        NamingStyle::Leaf => segment(&source.name),
+        NamingStyle::Kebab => words(parts).join("-").to_lowercase(),
+        NamingStyle::Snake => words(parts).join("_").to_lowercase(),
+        NamingStyle::Camel => words(parts)
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let w = w.to_lowercase();
+                if i == 0 {
+                    return w;
+                }
+                let mut chars = w.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect(),
+    };
+    if name.is_empty() {
+        FALLBACK_NAME.to_string()
+    } else {
+        name
+    }
+}
+
+fn suffix_separator(style: NamingStyle) -> &'static str {
+    match style {
+        NamingStyle::Snake => "_",
+        NamingStyle::Camel => "",
+        NamingStyle::Original | NamingStyle::Kebab | NamingStyle::Leaf => "-",
+    }
+}
+
+/// the words of some names, also splitting camel case words: getUserID gives get, User, ID
+fn words<'a>(names: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut words = vec![];
+    for name in names {
+        let name = transliterate(name);
+        for run in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+            let chars: Vec<char> = run.chars().collect();
+            let mut start = 0;
+            for i in 1..chars.len() {
+                if starts_word(&chars, i) {
+                    words.push(chars[start..i].iter().collect());
+                    start = i;
+                }
+            }
+            if start < chars.len() {
+                words.push(chars[start..].iter().collect());
+            }
+        }
+    }
+    words
+}
+
+/// a word starts at an upper case letter following a lower case one or a digit, as in getUser,
+/// or at the last capital of an acronym followed by lower case letters, as in HTTPServer. But IDs is a single word
+fn starts_word(chars: &[char], i: usize) -> bool {
+    if !chars[i].is_ascii_uppercase() {
+        return false;
+    }
+    if !chars[i - 1].is_ascii_uppercase() {
+        return true;
+    }
+    match &chars[i + 1..] {
+        ['s'] => false,
+        [next, ..] => next.is_ascii_lowercase(),
+        [] => false,
+    }
+}
+
+/// replaces accented latin letters by their ascii counterparts and drops any other non ascii character
+/// white space of any kind becomes a plain space, so that it still separates words
+fn transliterate(text: &str) -> String {
+    let mut ascii = String::new();
+    for c in text.chars() {
+        if c.is_ascii() {
+            ascii.push(c);
+        } else if c.is_whitespace() {
+            ascii.push(' ');
+        } else if let Some(t) = c.to_lowercase().next().and_then(latin) {
+            if c.is_uppercase() {
+                ascii.push_str(&t.to_uppercase());
+            } else {
+                ascii.push_str(t);
+            }
+        }
+    }
+    ascii
+}
+
+fn latin(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' | 'ĉ' | 'ċ' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::restclient::{HttpRequestBuilder, Method};
+
+    fn request(folder: &[&str], name: &str, id: Option<&str>) -> HttpRequest {
+        let mut request = HttpRequestBuilder::default()
+            .name("")
+            .method(Method::Get)
+            .url("http://host")
+            .build()
+            .unwrap();
+        request.set_source(RequestSource {
+            folder: folder.iter().map(|f| f.to_string()).collect(),
+            id: id.map(str::to_string),
+            name: name.to_string(),
+            ..Default::default()
+        });
+        request
+    }
+
+    fn named(requests: &mut [HttpRequest], style: NamingStyle) -> Vec<String> {
+        name_requests(requests, style);
+        requests.iter().map(|r| r.name().to_string()).collect()
+    }
+
+    #[test]
+    fn should_make_names_valid_identifiers() {
+        assert_eq!(segment("get user"), "get-user");
+        assert_eq!(segment("Crème brûlée / v1.2"), "Creme-brulee-v1-2");
+        assert_eq!(segment("🚀 \"launch\" ß"), "launch-ss");
+        assert_eq!(segment("list_users"), "list_users");
+        assert_eq!(segment("🚀"), "");
+        assert_eq!(
+            named(&mut [request(&["🚀"], "✨", None)], NamingStyle::Original),
+            vec!["request"]
+        );
+    }
+
+    #[test]
+    fn should_name_requests_in_every_style() {
+        let requests = || vec![request(&["Users", "Admin API"], "getUserID é", None)];
+        assert_eq!(
+            named(&mut requests(), NamingStyle::Original),
+            vec!["Users_Admin-API_getUserID-e"]
+        );
+        assert_eq!(
+            named(&mut requests(), NamingStyle::Kebab),
+            vec!["users-admin-api-get-user-id-e"]
+        );
+        assert_eq!(
+            named(&mut requests(), NamingStyle::Snake),
+            vec!["users_admin_api_get_user_id_e"]
+        );
+        assert_eq!(
+            named(&mut requests(), NamingStyle::Camel),
+            vec!["usersAdminApiGetUserIdE"]
+        );
+        assert_eq!(
+            named(&mut requests(), NamingStyle::Leaf),
+            vec!["getUserID-e"]
+        );
+    }
+
+    #[test]
+    fn should_split_acronyms_and_plurals_of_acronyms() {
+        let words = |name: &str| words([name.to_string()].iter());
+        assert_eq!(words("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(words("listIDs"), vec!["list", "IDs"]);
+        assert_eq!(words("v2Users"), vec!["v2", "Users"]);
+    }
+
+    #[test]
+    fn should_deduplicate_names_deterministically() {
+        let mut requests = vec![
+            request(&["A"], "get", None),
+            request(&["B"], "get", None),
+            request(&["C"], "get-2", None),
+            request(&["D"], "get", None),
+        ];
+        assert_eq!(
+            named(&mut requests, NamingStyle::Leaf),
+            vec!["get", "get-3", "get-2", "get-4"]
+        );
+    }
+
+    #[test]
+    fn should_map_item_ids_to_names() {
+        let mut requests = vec![
+            request(&["Users"], "get user", Some("8f2c")),
+            request(&[], "health", None),
+        ];
+        name_requests(&mut requests, NamingStyle::Original);
+        let mapping: serde_json::Value =
+            serde_json::from_str(&names_mapping(&requests).unwrap()).unwrap();
+        assert_eq!(
+            mapping,
+            serde_json::json!([
+                { "id": "8f2c", "path": "Users/get user", "name": "Users_get-user" },
+                { "path": "health", "name": "health" }
+            ])
+        );
+    }
+}
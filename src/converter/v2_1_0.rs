@@ -1,19 +1,42 @@
 //! converts a postman v 2.1.0 collection to RestClient http format
 
-use crate::{error::PostresError, postman::v2_1_0, restclient};
+use crate::{
+    config::ConversionOptions,
+    diagnostics::ItemOrigin,
+    error::PostresError,
+    postman::{item_name, v2_1_0},
+    report::Report,
+    restclient,
+};
 use fancy_regex::{Captures, Regex};
+use lazy_static::lazy_static;
 
-use super::make_base_name;
+use super::{make_base_name, naming::name_requests};
 
+mod auth;
 mod body;
+mod chaining;
+mod dynamic_variable;
+mod fidelity;
+mod graphql;
 mod header;
 mod method;
 mod request;
 mod url;
+mod variable;
 
+use auth::{apply_auth, inherit_auth, item_auth};
+use chaining::{chain_requests, response_captures};
+use dynamic_variable::convert_dynamic_variables;
+use fidelity::{record_dropped_features, record_dropped_scripts};
 use request::convert_request;
+use url::path_variables;
+use variable::{FileVariables, VariableScope};
 
-pub(crate) fn convert_to_http(spec: &v2_1_0::PostmanCollection) -> restclient::HttpRequests {
+pub(crate) fn convert_to_http(
+    spec: &v2_1_0::PostmanCollection,
+    options: &ConversionOptions,
+) -> (restclient::HttpRequests, Report) {
     // postman spec is confusing, ence comments to help you to figure out what is happening
     // Root object has a list of items whose name is item. Items can represent either requests or folders of requests
     // As http files have no concept of folders, we will convert the folder structure to a plain list of requests and try to diferentiate which request is in which folder through request name in http file
@@ -105,72 +128,171 @@ pub(crate) fn convert_to_http(spec: &v2_1_0::PostmanCollection) -> restclient::H
         5. After returning all items from the vector obtained from the first item on the original iterator, when the next item is requested, steps 1 to 4 are repeated, so that another item is obtained from the original iterator, the closure is applied, a vector of items is obtained, and its items are returned in sequence as the next items on the flat_map iterator.
         6. When the original iterator returns none, the flat_map returns also None as its iterator's next item, which ends the whole processing.
     */
-    let res = spec
+    // variables are collected while items are converted, because folders and requests can declare their own
+    let mut file_variables = FileVariables::default();
+    let scope = file_variables.add_collection_variables(&spec.variable);
+    // requests which can not be converted are left out. What went wrong with them is told by the report
+    let mut report = Report::default();
+    // collection scripts run around every request
+    record_dropped_scripts(
+        &spec.event,
+        &mut report.for_item(ItemOrigin {
+            pointer: "/event".to_string(),
+            ..Default::default()
+        }),
+    );
+    let mut http_requests: Vec<_> = spec
         .item
         .iter()
-        // base name is blank here because these are the items at the root level in the postman collection
+        .enumerate()
+        // there are no enclosing folders here because these are the items at the root level in the postman collection
         // notice that the convert_request_or_folder function takes an item and returns a vector of items
-        .flat_map(|i| convert_request_or_folder("", i))
-        .collect();
-    handle_errors(&res);
-    let http_requests = res
-        .into_iter()
-        .filter_map(|i| {
-            if i.is_ok() {
-                return Some(i.unwrap());
-            }
-            None
+        // auth declared at collection level applies to every request which does not declare its own
+        .flat_map(|(index, i)| {
+            convert_request_or_folder(
+                &[],
+                index,
+                i,
+                spec.auth.as_ref(),
+                &scope,
+                &mut file_variables,
+                options,
+                &mut report,
+            )
         })
         .collect();
+    // names are given once all requests are known, so that repeated names can be told apart
+    name_requests(&mut http_requests, options.naming);
+    // references to variables set by test scripts point to requests by name
+    chain_requests(&mut http_requests, options.one_file_per_folder, &mut report);
 
-    restclient::HttpRequests {
+    let http_requests = restclient::HttpRequests {
         requests: http_requests,
-    }
-}
-
-fn handle_errors(res: &Vec<Result<restclient::HttpRequest, PostresError>>) {
-    let errors: Vec<_> = res
-        .iter()
-        .filter_map(|i| {
-            if let Err(e) = i {
-                return Some(e);
-            }
-            None
-        })
-        .collect();
-    if !errors.is_empty() {
-        println!("Process terminated with errors:");
-        for i in errors {
-            println!("{i}");
-        }
-    }
+        variables: file_variables.into_variables(),
+    };
+    (http_requests, report)
 }
 
+/// folders holds the names of the folders enclosing the item, from the outermost, and index its position among its siblings, which names items without a name
+#[allow(clippy::too_many_arguments)]
 fn convert_request_or_folder(
-    basename: &str,
+    folders: &[String],
+    index: usize,
     item: &v2_1_0::Items,
-) -> Vec<Result<restclient::HttpRequest, PostresError>> {
-    let name = make_base_name(basename, item.name.as_ref().unwrap());
+    parent_auth: Option<&v2_1_0::Auth>,
+    parent_scope: &VariableScope,
+    file_variables: &mut FileVariables,
+    options: &ConversionOptions,
+    report: &mut Report,
+) -> Vec<restclient::HttpRequest> {
+    // names of the enclosing folders and of the item itself
+    let own_name = item_name(item, index);
+    let path = [folders, std::slice::from_ref(&own_name)].concat();
+    let name = path.iter().fold(String::new(), |basename, name| {
+        make_base_name(&basename, name)
+    });
+    // placeholders left by lenient parsing are reported. Children of a broken folder are still converted, with what their parents declare
+    if let Some(msg) = &item.parse_error {
+        report
+            .for_item(item_origin(&name, item))
+            .error(PostresError::PostmanItemParsingError { msg: msg.clone() });
+        if is_request(item) {
+            return vec![];
+        }
+        return convert_folder(
+            &path,
+            &item.item,
+            parent_auth,
+            parent_scope,
+            file_variables,
+            options,
+            report,
+        );
+    }
+    let scope = file_variables.add_item_variables(&name, &item.variable, parent_scope);
+    let auth = inherit_auth(parent_auth, item_auth(item));
+    if !is_request(item) {
+        record_dropped_features(item, &mut report.for_item(item_origin(&name, item)));
+    }
     if is_request(item) {
+        // everything reported about a request is located at it
+        let mut report = report.for_item(item_origin(&name, item));
         // convert request and return a vec with it
-        return vec![convert_request(&name, item)];
+        return match convert_request(&name, item, options, &mut report) {
+            Ok(mut r) => {
+                record_dropped_features(item, &mut report);
+                r.set_source(restclient::RequestSource {
+                    folder: folders.to_vec(),
+                    id: item.id.clone(),
+                    name: own_name,
+                    captures: response_captures(&item.event, &mut report),
+                    origin: item_origin(&name, item),
+                });
+                // auth is applied before variables are converted, because credentials usually reference variables
+                apply_auth(&mut r, auth, &mut report);
+                // path variables were converted to {{variable}} references while the url was converted. Only the url can see their values
+                // they are only declared for requests which are written, so a failed request leaves no stray variables behind
+                let path_scope = file_variables.add_path_variables(&name, path_variables(item));
+                r.rewrite_url(|content| path_scope.convert_variables(content));
+                r.rewrite_text(|content| scope.convert_variables(content));
+                convert_dynamic_variables(&mut r, options.faker, &mut report);
+                vec![r]
+            }
+            Err(e) => {
+                report.error(e);
+                vec![]
+            }
+        };
     }
     // process recursively the list of requests
-    convert_folder(&name, &item.item)
+    convert_folder(
+        &path,
+        &item.item,
+        auth,
+        &scope,
+        file_variables,
+        options,
+        report,
+    )
 }
 
 fn convert_folder(
-    name: &str,
+    folders: &[String],
     items: &Option<Vec<v2_1_0::Items>>,
-) -> Vec<Result<restclient::HttpRequest, PostresError>> {
+    auth: Option<&v2_1_0::Auth>,
+    scope: &VariableScope,
+    file_variables: &mut FileVariables,
+    options: &ConversionOptions,
+    report: &mut Report,
+) -> Vec<restclient::HttpRequest> {
     items
         .as_ref()
         .unwrap()
         .iter()
-        .flat_map(|i| convert_request_or_folder(&name, i))
+        .enumerate()
+        .flat_map(|(index, i)| {
+            convert_request_or_folder(
+                folders,
+                index,
+                i,
+                auth,
+                scope,
+                file_variables,
+                options,
+                report,
+            )
+        })
         .collect()
 }
 
+/// items loaded from files know where they come from. Collections built in memory only have names
+fn item_origin(name: &str, item: &v2_1_0::Items) -> ItemOrigin {
+    item.origin.clone().unwrap_or_else(|| ItemOrigin {
+        path: name.to_string(),
+        ..Default::default()
+    })
+}
+
 fn is_request(item: &v2_1_0::Items) -> bool {
     // An object called items (plural) can perfectly signify a request (not a folder).
     // If it is a request, it iis a leaf in reqests tree
@@ -180,16 +302,13 @@ fn is_request(item: &v2_1_0::Items) -> bool {
     matches!(item.item.as_ref(), Some(i) if i.is_empty())
 }
 
-fn convert_variables(content: &str) -> String {
-    unimplemented!()
-}
-
 fn extract_path_variables(content: &str) -> String {
-    let re_path_variables = Regex::new(r#"\/\:(.*?)(\/|$)"#).unwrap();
-    let res = re_path_variables.replace_all(content, |caps: &Captures| {
-        format!("/{{{{{}}}}}{}", &caps[1], &caps[2])
-    });
-    res.to_string()
+    lazy_static! {
+        // a path variable ends where its path segment ends, which might also be where the query string or the fragment starts
+        static ref RE: Regex = Regex::new(r#"\/\:([^\/\?#]+)"#).unwrap();
+    }
+    RE.replace_all(content, |caps: &Captures| format!("/{{{{{}}}}}", &caps[1]))
+        .to_string()
 }
 
 /*
@@ -197,7 +316,10 @@ fn extract_path_variables(content: &str) -> String {
 */
 #[cfg(test)]
 mod tests {
-    use crate::postman::v2_1_0;
+    use crate::{
+        diagnostics::{Diagnostic, Location},
+        postman::v2_1_0,
+    };
 
     use super::*;
 
@@ -221,6 +343,106 @@ mod tests {
         assert_eq!(is_request(&req), false);
     }
 
+    #[test]
+    fn should_tell_which_item_an_error_happened_in() {
+        let origin = ItemOrigin {
+            path: "Users/create user".to_string(),
+            pointer: "/item/0/item/1".to_string(),
+            location: Some(Location {
+                line: 12,
+                column: 9,
+            }),
+        };
+        let mut item = default_postman_items();
+        item.name = Some("create user".to_string());
+        item.request = Some(v2_1_0::RequestUnion::RequestClass(v2_1_0::RequestClass {
+            method: Some("GET ME".to_string()),
+            url: Some(v2_1_0::Url::String("http://host".to_string())),
+            ..default_postman_request_class()
+        }));
+        item.origin = Some(origin.clone());
+        let mut report = Report::default();
+        let res = convert_request_or_folder(
+            &["Users".to_string()],
+            0,
+            &item,
+            None,
+            &VariableScope::default(),
+            &mut FileVariables::default(),
+            &ConversionOptions::default(),
+            &mut report,
+        );
+        assert!(res.is_empty());
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].diagnostic, Diagnostic::error(origin));
+        assert_eq!(
+            report.entries[0].message,
+            PostresError::invalid_postman_method("GET ME").to_string()
+        );
+    }
+
+    #[test]
+    fn should_not_declare_path_variables_of_requests_which_fail() {
+        let mut item = default_postman_items();
+        item.name = Some("get user".to_string());
+        item.request = Some(v2_1_0::RequestUnion::RequestClass(v2_1_0::RequestClass {
+            method: Some("GET ME".to_string()),
+            url: Some(v2_1_0::Url::UrlClass(v2_1_0::UrlClass {
+                raw: Some("http://host/users/:id".to_string()),
+                variable: Some(vec![v2_1_0::Variable {
+                    key: Some("id".to_string()),
+                    value: Some(serde_json::json!("1")),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            })),
+            ..default_postman_request_class()
+        }));
+        let mut file_variables = FileVariables::default();
+        let res = convert_request_or_folder(
+            &[],
+            0,
+            &item,
+            None,
+            &VariableScope::default(),
+            &mut file_variables,
+            &ConversionOptions::default(),
+            &mut Report::default(),
+        );
+        assert!(res.is_empty());
+        assert!(file_variables.into_variables().is_empty());
+    }
+
+    #[test]
+    fn should_name_nameless_items_by_their_position() {
+        let collection: v2_1_0::PostmanCollection = serde_json::from_value(serde_json::json!({
+            "info": { "name": "c", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+            "item": [
+                { "request": "http://host/a" },
+                { "item": [
+                    { "name": "b", "request": "http://host/b" },
+                    { "request": "http://host/c" }
+                ] }
+            ]
+        }))
+        .unwrap();
+        let (requests, report) = convert_to_http(&collection, &ConversionOptions::default());
+        assert_eq!(report.entries.len(), 0);
+        let sources: Vec<_> = requests
+            .requests
+            .iter()
+            .map(|r| (r.source().folder.clone(), r.source().name.clone()))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                (vec![], "item 1".to_string()),
+                (vec!["item 2".to_string()], "b".to_string()),
+                (vec!["item 2".to_string()], "item 2".to_string()),
+            ]
+        );
+    }
+
     pub fn default_postman_items() -> v2_1_0::Items {
         v2_1_0::Items {
             description: Default::default(),
@@ -233,6 +455,9 @@ mod tests {
             variable: Default::default(),
             auth: Default::default(),
             item: Default::default(),
+            parse_error: Default::default(),
+            origin: Default::default(),
+            upgrade_losses: Default::default(),
         }
     }
 
@@ -0,0 +1,318 @@
+//! converts postman authorization helpers to RestClient headers and query parameters
+
+use crate::{
+    postman::v2_1_0::{self, Auth, AuthAttribute, AuthType},
+    report::{Feature, ItemReport},
+    restclient::{self, Header, QueryParam},
+};
+
+/// the auth a postman item declares for itself, if any
+/// folders declare auth at item level, requests declare it inside the request object
+pub(crate) fn item_auth(item: &v2_1_0::Items) -> Option<&Auth> {
+    let request_auth = match &item.request {
+        Some(v2_1_0::RequestUnion::RequestClass(r)) => r.auth.as_ref(),
+        _ => None,
+    };
+    request_auth.or(item.auth.as_ref())
+}
+
+/// resolves which auth applies to an item the way postman does it
+/// an item without auth inherits its parent's. An item with noauth explicitly opts out, which is also inherited by its children
+pub(crate) fn inherit_auth<'a>(
+    parent: Option<&'a Auth>,
+    own: Option<&'a Auth>,
+) -> Option<&'a Auth> {
+    own.or(parent)
+}
+
+/// adds to the request the headers or query parameters RestClient needs to authenticate it
+pub(crate) fn apply_auth(
+    request: &mut restclient::HttpRequest,
+    auth: Option<&Auth>,
+    report: &mut ItemReport,
+) {
+    let auth = match auth {
+        Some(a) => a,
+        None => return,
+    };
+    let credentials = match &auth.auth_type {
+        AuthType::Noauth => return,
+        AuthType::Apikey => api_key(&auth.apikey),
+        AuthType::Awsv4 => Credentials::Header(aws(&auth.awsv4)),
+        AuthType::Basic => Credentials::Header(format!(
+            "Basic {}:{}",
+            attribute(&auth.basic, "username"),
+            attribute(&auth.basic, "password")
+        )),
+        AuthType::Bearer => {
+            Credentials::Header(format!("Bearer {}", attribute(&auth.bearer, "token")))
+        }
+        AuthType::Digest => Credentials::Header(format!(
+            "Digest {} {}",
+            attribute(&auth.digest, "username"),
+            attribute(&auth.digest, "password")
+        )),
+        AuthType::Edgegrid => return unsupported(&auth.auth_type, Feature::EdgegridAuth, report),
+        AuthType::Hawk => return unsupported(&auth.auth_type, Feature::HawkAuth, report),
+        AuthType::Ntlm => return unsupported(&auth.auth_type, Feature::NtlmAuth, report),
+        AuthType::Oauth1 => return unsupported(&auth.auth_type, Feature::Oauth1Auth, report),
+        AuthType::Oauth2 => return unsupported(&auth.auth_type, Feature::Oauth2Auth, report),
+    };
+    match credentials {
+        Credentials::Header(value) => {
+            add_header(request, "Authorization".to_string(), value, report)
+        }
+        Credentials::CustomHeader(name, value) => add_header(request, name, value, report),
+        Credentials::QueryParam(name, value) => {
+            request.add_query_param(QueryParam::new(name, value))
+        }
+    }
+}
+
+/// auth types RestClient can not send are dropped. The request is still converted, so that credentials can be added by hand
+/// the request would be sent without credentials, so besides being counted as dropped it is warned about
+fn unsupported(auth_type: &AuthType, feature: Feature, report: &mut ItemReport) {
+    report.warning(format!(
+        "{auth_type:?} authorization is not supported by RestClient and was not converted"
+    ));
+    report.dropped(
+        feature,
+        "RestClient does not support this authorization type",
+    );
+}
+
+/// where the credentials go in the request
+enum Credentials {
+    /// value of the Authorization header
+    Header(String),
+    /// name and value of a header other than Authorization
+    CustomHeader(String, String),
+    /// name and value of a query parameter
+    QueryParam(String, String),
+}
+
+fn add_header(
+    request: &mut restclient::HttpRequest,
+    name: String,
+    value: String,
+    report: &mut ItemReport,
+) {
+    // a header explicitly set in the request wins over the auth helper
+    if !request.add_header_if_absent(Header {
+        enabled: true,
+        name: name.clone(),
+        value,
+    }) {
+        report.warning(format!(
+            "header {name} is explicitly set, authorization was not applied"
+        ));
+    }
+}
+
+fn api_key(attributes: &Option<Vec<AuthAttribute>>) -> Credentials {
+    let name = attribute(attributes, "key");
+    let value = attribute(attributes, "value");
+    // postman sends api keys as headers unless told otherwise
+    match attribute(attributes, "in").as_str() {
+        "query" => Credentials::QueryParam(name, value),
+        _ => Credentials::CustomHeader(name, value),
+    }
+}
+
+fn aws(attributes: &Option<Vec<AuthAttribute>>) -> String {
+    // RestClient syntax is AWS <accessId> <accessKey> [token:<sessionToken>] [region:<regionName>] [service:<serviceName>]
+    let mut value = format!(
+        "AWS {} {}",
+        attribute(attributes, "accessKey"),
+        attribute(attributes, "secretKey")
+    );
+    for (key, prefix) in [
+        ("sessionToken", "token"),
+        ("region", "region"),
+        ("service", "service"),
+    ] {
+        let optional = attribute(attributes, key);
+        if !optional.is_empty() {
+            value.push_str(&format!(" {prefix}:{optional}"));
+        }
+    }
+    value
+}
+
+/// value of an auth attribute, or an empty string if it is not present
+fn attribute(attributes: &Option<Vec<AuthAttribute>>, key: &str) -> String {
+    let value = attributes
+        .iter()
+        .flatten()
+        .find(|a| a.key == key)
+        .and_then(|a| a.value.as_ref());
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::report::Report;
+
+    fn auth(auth_type: AuthType, attributes: serde_json::Value) -> Auth {
+        let mut auth: Auth = serde_json::from_value(json!({ "type": "noauth" })).unwrap();
+        let attributes: Option<Vec<AuthAttribute>> = serde_json::from_value(attributes).unwrap();
+        match auth_type {
+            AuthType::Apikey => auth.apikey = attributes,
+            AuthType::Awsv4 => auth.awsv4 = attributes,
+            AuthType::Basic => auth.basic = attributes,
+            AuthType::Bearer => auth.bearer = attributes,
+            AuthType::Digest => auth.digest = attributes,
+            AuthType::Oauth2 => auth.oauth2 = attributes,
+            _ => {}
+        }
+        auth.auth_type = auth_type;
+        auth
+    }
+
+    fn request() -> restclient::HttpRequest {
+        restclient::HttpRequestBuilder::default()
+            .name("req")
+            .method(restclient::Method::Get)
+            .url("http://host/path")
+            .build()
+            .unwrap()
+    }
+
+    fn authorized_reporting(auth: &Auth, report: &mut Report) -> restclient::HttpRequest {
+        let mut req = request();
+        apply_auth(
+            &mut req,
+            Some(auth),
+            &mut report.for_item(Default::default()),
+        );
+        req
+    }
+
+    fn authorized(auth: &Auth) -> restclient::HttpRequest {
+        authorized_reporting(auth, &mut Report::default())
+    }
+
+    fn with_header(name: &str, value: &str) -> restclient::HttpRequest {
+        let mut req = request();
+        req.add_header_if_absent(Header {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+        req
+    }
+
+    #[test]
+    fn should_prefer_own_auth_over_inherited_one() {
+        let parent = auth(AuthType::Bearer, json!([]));
+        let own = auth(AuthType::Noauth, json!(null));
+        assert_eq!(inherit_auth(Some(&parent), None), Some(&parent));
+        assert_eq!(inherit_auth(Some(&parent), Some(&own)), Some(&own));
+        assert_eq!(inherit_auth(None, None), None);
+    }
+
+    #[test]
+    fn should_convert_basic_bearer_and_digest() {
+        let credentials = json!([
+            { "key": "username", "value": "user", "type": "string" },
+            { "key": "password", "value": "{{pass}}", "type": "string" }
+        ]);
+        assert_eq!(
+            authorized(&auth(AuthType::Basic, credentials.clone())),
+            with_header("Authorization", "Basic user:{{pass}}")
+        );
+        assert_eq!(
+            authorized(&auth(AuthType::Digest, credentials)),
+            with_header("Authorization", "Digest user {{pass}}")
+        );
+        assert_eq!(
+            authorized(&auth(
+                AuthType::Bearer,
+                json!([{ "key": "token", "value": "abc", "type": "string" }])
+            )),
+            with_header("Authorization", "Bearer abc")
+        );
+    }
+
+    #[test]
+    fn should_convert_api_key_in_header_or_query() {
+        let header = json!([
+            { "key": "key", "value": "X-API-KEY" },
+            { "key": "value", "value": "secret" }
+        ]);
+        assert_eq!(
+            authorized(&auth(AuthType::Apikey, header)),
+            with_header("X-API-KEY", "secret")
+        );
+        let query = json!([
+            { "key": "key", "value": "api_key" },
+            { "key": "value", "value": "secret" },
+            { "key": "in", "value": "query" }
+        ]);
+        let mut expected = request();
+        expected.add_query_param(QueryParam::new("api_key", "secret"));
+        assert_eq!(authorized(&auth(AuthType::Apikey, query)), expected);
+    }
+
+    #[test]
+    fn should_convert_aws_signature() {
+        let attributes = json!([
+            { "key": "accessKey", "value": "id" },
+            { "key": "secretKey", "value": "key" },
+            { "key": "region", "value": "us-east-1" },
+            { "key": "service", "value": "s3" }
+        ]);
+        assert_eq!(
+            authorized(&auth(AuthType::Awsv4, attributes)),
+            with_header("Authorization", "AWS id key region:us-east-1 service:s3")
+        );
+    }
+
+    #[test]
+    fn should_leave_request_untouched_for_noauth_and_unsupported_types() {
+        let mut report = Report::default();
+        assert_eq!(
+            authorized_reporting(&auth(AuthType::Noauth, json!(null)), &mut report),
+            request()
+        );
+        assert_eq!(report.warnings(), 0);
+        assert_eq!(
+            authorized_reporting(
+                &auth(
+                    AuthType::Oauth2,
+                    json!([{ "key": "accessToken", "value": "abc" }])
+                ),
+                &mut report
+            ),
+            request()
+        );
+        assert_eq!(report.warnings(), 1);
+        assert_eq!(report.dropped[0].feature, Feature::Oauth2Auth);
+    }
+
+    #[test]
+    fn should_not_replace_explicit_authorization_header() {
+        let mut req = with_header("authorization", "Custom x");
+        let mut report = Report::default();
+        apply_auth(
+            &mut req,
+            Some(&auth(
+                AuthType::Bearer,
+                json!([{ "key": "token", "value": "abc" }]),
+            )),
+            &mut report.for_item(Default::default()),
+        );
+        assert_eq!(req, with_header("authorization", "Custom x"));
+        assert_eq!(report.warnings(), 1);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{error::PostresError, postman::v2_1_0, restclient};
 
+use super::graphql::pretty_variables;
+
 /*
     def 017: Generic programming
 
@@ -328,7 +330,11 @@ fn assert_has_elements<'a, T>(
     The borrow checker  can infer lifetimes for a huge amount of situations. This hasn't always been so. We don't know if inferences for situations like this will ever be authomatically provided. What we know though is that every time rust is in doubt about how to make sure our code isn't misbehaving, it will ask us for further clarifications.
 */
 
-fn convert_body(postman_req: &v2_1_0::RequestClass) -> Result<restclient::Body, PostresError> {
+/// disabled form fields and url encoded parameters are left out, unless keep_disabled is set
+pub(crate) fn convert_body(
+    postman_req: &v2_1_0::RequestClass,
+    keep_disabled: bool,
+) -> Result<restclient::Body, PostresError> {
     if postman_req
         .body
         .as_ref()
@@ -346,14 +352,52 @@ fn convert_body(postman_req: &v2_1_0::RequestClass) -> Result<restclient::Body,
     }
     let body = match postman_request_body.mode.as_ref().unwrap() {
         v2_1_0::Mode::File => http_body_from_file(&postman_request_body.file)?,
-        v2_1_0::Mode::Formdata => http_body_from_form_data(&postman_request_body.formdata)?,
+        v2_1_0::Mode::Formdata => {
+            http_body_from_form_data(&postman_request_body.formdata, keep_disabled)?
+        }
         v2_1_0::Mode::Graphql => http_body_from_graphql(&postman_request_body.graphql)?,
         v2_1_0::Mode::Raw => http_body_from_raw(&postman_request_body.raw)?,
-        v2_1_0::Mode::Urlencoded => http_body_from_url_encoded(&postman_request_body.urlencoded)?,
+        v2_1_0::Mode::Urlencoded => {
+            http_body_from_url_encoded(&postman_request_body.urlencoded, keep_disabled)?
+        }
     };
     Ok(body)
 }
 
+/// the content type postman sends implicitly with a converted body, if any
+/// multipart bodies impose their own content type, which has to carry the boundary (see restclient::Body::headers)
+pub(crate) fn content_type(
+    postman_req: &v2_1_0::RequestClass,
+    body: &restclient::Body,
+) -> Option<&'static str> {
+    match body {
+        // postman does not send a body, nor its content type, when the raw text is empty
+        restclient::Body::Raw(raw) if raw.is_empty() => None,
+        restclient::Body::Raw(_) => {
+            let language = postman_req
+                .body
+                .as_ref()
+                .and_then(|b| b.options.as_ref())
+                .and_then(|o| o.pointer("/raw/language"))
+                .and_then(|l| l.as_str());
+            // text is the language postman picks for raw bodies when none is chosen
+            match language.unwrap_or("text") {
+                "json" => Some("application/json"),
+                "xml" => Some("application/xml"),
+                "html" => Some("text/html"),
+                "javascript" => Some("application/javascript"),
+                "text" => Some("text/plain"),
+                _ => None,
+            }
+        }
+        restclient::Body::UrlEncoded(_) => Some("application/x-www-form-urlencoded"),
+        restclient::Body::Graphql(_) => Some("application/json"),
+        restclient::Body::Empty
+        | restclient::Body::FileSource(_)
+        | restclient::Body::FormData(_) => None,
+    }
+}
+
 fn http_body_from_raw(raw: &Option<String>) -> Result<restclient::Body, PostresError> {
     let raw = assert_postman_field_has_value(
         raw,
@@ -462,6 +506,7 @@ fn http_body_from_file(file: &Option<v2_1_0::File>) -> Result<restclient::Body,
 
 fn http_body_from_form_data(
     form_data: &Option<Vec<v2_1_0::FormParameter>>,
+    keep_disabled: bool,
 ) -> Result<restclient::Body, PostresError> {
     let form_data = assert_postman_field_has_value(
         form_data,
@@ -485,7 +530,7 @@ fn http_body_from_form_data(
         The patterns can also be used in pattern matching. The difference is that the match statement tries to match a series of patterns, while a function parameter with this destructuring requires that the parameter is of a given type, thus if the pattern you're using hhere does not fit on that type you will get an error.
     */
     .filter(|v2_1_0::FormParameter { disabled, .. }| {
-        disabled.is_none() || matches!(disabled, Some(d) if *d == false)
+        keep_disabled || disabled.is_none() || matches!(disabled, Some(d) if *d == false)
     })
     /*
         def 022: inference and the _ symbol
@@ -510,6 +555,7 @@ fn http_body_from_form_data(
                 .unwrap_or("text".to_string());
             Ok(restclient::FormDataParamSpec {
                 content_type: postman_form_parameter.content_type.clone(),
+                enabled: postman_form_parameter.disabled != Some(true),
                 name: postman_form_parameter.key.clone(),
                 // remember that a match block returns the result of the matched expression and we can therefore assign that value to a variable pof rield
                 value: match form_parameter_type.as_str() {
@@ -614,6 +660,12 @@ fn http_body_from_form_data(
             If all elements are successful, the question mark operator unwraps the result from Ok and assigns the collected vector into the converted_form_data variable, that we return as the data the restclient::Body::FormData requires, all inside an Ok variant, because the http_body_from_form_data function also returns a std::Result enum.
         */        
         .collect::<Result<Vec<_>, PostresError>>()?;
+    // a form whose fields are all disabled sends nothing, even if the fields are kept
+    if !converted_form_data.iter().any(|p| p.enabled) {
+        return Err(PostresError::EmptyListOfPostmanItemsError {
+            field: "form data".to_string(),
+        });
+    }
 
     Ok(restclient::Body::FormData(converted_form_data))
 }
@@ -700,35 +752,48 @@ fn http_body_from_graphql(
         .ok_or_else(|| PostresError::InvalidPostmanGraphqlSpecification {
             msg: "Could not extract query of mutation from graphql body".to_string(),
         })?;
-    let variables = graphql
-        .pointer("/variables")
-        .and_then(|q| q.as_str())
-        .map(|q| q.to_string());
+    // variables are usually json typed as text, but collections written by other tools might hold them as a json object
+    // invalid variables are kept as they were typed, so that nothing is lost. They are reported when the request is checked (see graphql.rs)
+    let variables = match graphql.pointer("/variables") {
+        Some(serde_json::Value::String(v)) if !v.trim().is_empty() => {
+            Some(pretty_variables(v).unwrap_or_else(|_| v.clone()))
+        }
+        Some(serde_json::Value::String(_)) | Some(serde_json::Value::Null) | None => None,
+        Some(v) => Some(pretty_variables(&v.to_string()).unwrap_or_else(|_| v.to_string())),
+    };
+    let operation_name = graphql
+        .pointer("/operationName")
+        .and_then(|o| o.as_str())
+        .filter(|o| !o.trim().is_empty())
+        .map(|o| o.trim().to_string());
     Ok(restclient::Body::Graphql(restclient::GraphqlSpec {
         spec,
         variables,
+        operation_name,
     }))
 }
 
 fn http_body_from_url_encoded(
     parameters: &Option<Vec<v2_1_0::UrlEncodedParameter>>,
+    keep_disabled: bool,
 ) -> Result<restclient::Body, PostresError> {
     let parameters =
-        assert_postman_field_has_value(parameters, PostresError::PostmanUrlEncodedSpecNotPresent)?
-            .iter()
-            .filter(|param| {
-                param.disabled.is_none() || matches!(param.disabled, Some(p) if p == false)
-            })
-            .collect::<Vec<&v2_1_0::UrlEncodedParameter>>();
-    if parameters.is_empty() {
+        assert_postman_field_has_value(parameters, PostresError::PostmanUrlEncodedSpecNotPresent)?;
+    // a body whose parameters are all disabled sends nothing, even if the parameters are kept
+    if parameters.iter().all(|param| param.disabled == Some(true)) {
         return Err(PostresError::PostmanUrlEncodedSpecNotPresent);
     }
     let mut params = vec![];
     for postmanParam in parameters {
-        params.push(restclient::QueryParam::new(
-            postmanParam.key.clone(),
-            postmanParam.value.as_ref().cloned().unwrap_or_default(),
-        ));
+        let key = postmanParam.key.clone();
+        let value = postmanParam.value.as_ref().cloned().unwrap_or_default();
+        match postmanParam.disabled {
+            Some(true) if keep_disabled => {
+                params.push(restclient::QueryParam::disabled(key, value))
+            }
+            Some(true) => {}
+            _ => params.push(restclient::QueryParam::new(key, value)),
+        }
     }
     Ok(restclient::Body::UrlEncoded(params))
 }
@@ -744,10 +809,53 @@ mod tests {
     use super::super::tests::*;
     use super::*;
 
+    #[test]
+    fn should_infer_content_type_from_body_mode_and_raw_language() {
+        let content_type_of = |body: serde_json::Value| {
+            let mut postman_request = default_postman_request_class();
+            postman_request.body = Some(serde_json::from_value(body).unwrap());
+            let body = convert_body(&postman_request, false).unwrap();
+            content_type(&postman_request, &body)
+        };
+        assert_eq!(
+            content_type_of(
+                json!({"mode": "raw", "raw": "{}", "options": {"raw": {"language": "json"}}})
+            ),
+            Some("application/json")
+        );
+        assert_eq!(
+            content_type_of(
+                json!({"mode": "raw", "raw": "<a/>", "options": {"raw": {"language": "xml"}}})
+            ),
+            Some("application/xml")
+        );
+        assert_eq!(
+            content_type_of(json!({"mode": "raw", "raw": "hi"})),
+            Some("text/plain")
+        );
+        assert_eq!(content_type_of(json!({"mode": "raw", "raw": ""})), None);
+        assert_eq!(
+            content_type_of(
+                json!({"mode": "urlencoded", "urlencoded": [{"key": "a", "value": "1"}]})
+            ),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(
+            content_type_of(json!({"mode": "graphql", "graphql": {"query": "{ a }"}})),
+            Some("application/json")
+        );
+        assert_eq!(
+            content_type_of(
+                json!({"mode": "raw", "raw": "{}", "disabled": true, "options": {"raw": {"language": "json"}}})
+            ),
+            None
+        );
+    }
+
     #[test]
     fn should_return_empty_request_body_when_postman_request_body_is_not_provided() {
         let postman_request = default_postman_request_class();
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(res, restclient::Body::Empty);
     }
 
@@ -758,7 +866,7 @@ mod tests {
             disabled: Some(true),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(res, restclient::Body::Empty);
     }
 
@@ -769,7 +877,7 @@ mod tests {
             raw: Some("request_body".to_string()),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(res, restclient::Body::Raw("request_body".to_string()));
     }
 
@@ -781,7 +889,7 @@ mod tests {
             raw: Some("request_body".to_string()),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(res, restclient::Body::Raw("request_body".to_string()));
     }
 
@@ -793,7 +901,7 @@ mod tests {
             raw: None,
             ..Default::default()
         });
-        let res = convert_body(&postman_request);
+        let res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -808,12 +916,13 @@ mod tests {
             })),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(
             res,
             restclient::Body::Graphql(restclient::GraphqlSpec {
                 spec: "query(2, 2) { result }".to_string(),
                 variables: Some("a: 1".to_string()),
+                operation_name: None,
             })
         );
     }
@@ -828,12 +937,36 @@ mod tests {
             })),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(
             res,
             restclient::Body::Graphql(restclient::GraphqlSpec {
                 spec: "query(2, 2) { result }".to_string(),
                 variables: None,
+                operation_name: None,
+            })
+        );
+    }
+
+    #[test]
+    fn should_convert_to_graphql_body_with_object_variables_and_operation_name() {
+        let mut postman_request = default_postman_request_class();
+        postman_request.body = Some(v2_1_0::Body {
+            mode: Some(v2_1_0::Mode::Graphql),
+            graphql: Some(json!({
+                "query": "query User($id: ID!) { user(id: $id) { name } }",
+                "variables": { "id": "{{userId}}" },
+                "operationName": "User"
+            })),
+            ..Default::default()
+        });
+        let res = convert_body(&postman_request, false).unwrap();
+        assert_eq!(
+            res,
+            restclient::Body::Graphql(restclient::GraphqlSpec {
+                spec: "query User($id: ID!) { user(id: $id) { name } }".to_string(),
+                variables: Some("{\n  \"id\": \"{{userId}}\"\n}".to_string()),
+                operation_name: Some("User".to_string()),
             })
         );
     }
@@ -846,7 +979,7 @@ mod tests {
             graphql: Some(json!({})),
             ..Default::default()
         });
-        let res = convert_body(&postman_request);
+        let res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -858,7 +991,7 @@ mod tests {
             graphql: Some(json!({"query": 1})),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -870,7 +1003,7 @@ mod tests {
             mode: Some(v2_1_0::Mode::Urlencoded),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
         // url encoded parameter comtainimng empty list
         postman_request
@@ -879,7 +1012,7 @@ mod tests {
             .unwrap()
             .urlencoded
             .insert(vec![]);
-        res = convert_body(&postman_request);
+        res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -900,7 +1033,7 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -931,7 +1064,7 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(
             res,
             restclient::Body::UrlEncoded(vec![
@@ -949,7 +1082,7 @@ mod tests {
             mode: Some(v2_1_0::Mode::File),
             ..Default::default()
         });
-        let res = convert_body(&postman_request);
+        let res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -961,7 +1094,7 @@ mod tests {
             file: Some(v2_1_0::File::default()),
             ..Default::default()
         });
-        let res = convert_body(&postman_request);
+        let res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -976,7 +1109,7 @@ mod tests {
             }),
             ..Default::default()
         });
-        let res = convert_body(&postman_request);
+        let res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -992,7 +1125,7 @@ mod tests {
             }),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(res, restclient::Body::FileSource("file source".to_string()));
     }
 
@@ -1008,7 +1141,7 @@ mod tests {
             }),
             ..Default::default()
         });
-        let res = convert_body(&postman_request).unwrap();
+        let res = convert_body(&postman_request, false).unwrap();
         assert_eq!(res, restclient::Body::Raw("content".to_string()));
     }
 
@@ -1019,10 +1152,10 @@ mod tests {
             mode: Some(v2_1_0::Mode::Formdata),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
         postman_request.body.as_mut().unwrap().formdata.insert(vec![]);
-        res = convert_body(&postman_request);
+        res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -1043,10 +1176,60 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
+    #[test]
+    fn should_keep_disabled_form_fields_and_url_encoded_parameters_if_asked_to() {
+        let body_of = |body: serde_json::Value, keep_disabled: bool| {
+            let mut postman_request = default_postman_request_class();
+            postman_request.body = Some(serde_json::from_value(body).unwrap());
+            convert_body(&postman_request, keep_disabled)
+        };
+        let form = json!({"mode": "formdata", "formdata": [
+            {"key": "title", "value": "hello", "type": "text"},
+            {"key": "avatar", "src": "me.png", "type": "file", "disabled": true}
+        ]});
+        assert_eq!(
+            body_of(form, true).unwrap(),
+            restclient::Body::FormData(vec![
+                restclient::FormDataParamSpec {
+                    content_type: None,
+                    enabled: true,
+                    name: "title".to_string(),
+                    value: restclient::FormParamValue::Text("hello".to_string())
+                },
+                restclient::FormDataParamSpec {
+                    content_type: None,
+                    enabled: false,
+                    name: "avatar".to_string(),
+                    value: restclient::FormParamValue::File(vec!["me.png".to_string()])
+                },
+            ])
+        );
+        let url_encoded = json!({"mode": "urlencoded", "urlencoded": [
+            {"key": "user", "value": "me"},
+            {"key": "remember", "value": "true", "disabled": true}
+        ]});
+        assert_eq!(
+            body_of(url_encoded.clone(), true).unwrap(),
+            restclient::Body::UrlEncoded(vec![
+                restclient::QueryParam::new("user", "me"),
+                restclient::QueryParam::disabled("remember", "true"),
+            ])
+        );
+        assert_eq!(
+            body_of(url_encoded, false).unwrap(),
+            restclient::Body::UrlEncoded(vec![restclient::QueryParam::new("user", "me")])
+        );
+        // nothing would be sent, whatever is kept
+        let all_disabled = json!({"mode": "urlencoded", "urlencoded": [
+            {"key": "remember", "value": "true", "disabled": true}
+        ]});
+        assert!(body_of(all_disabled, true).is_err());
+    }
+
     #[test]    
     fn should_convert_to_rest_client_body_of_type_formdata_with_text_content_when_postman_form_data_parameter_does_not_specify_parameter_type() {
         let mut postman_request = default_postman_request_class();
@@ -1061,9 +1244,9 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request).unwrap();        
+        let mut res = convert_body(&postman_request, false).unwrap();        
         assert_eq!(res, restclient::Body::FormData(vec![
-            restclient::FormDataParamSpec { content_type: None, name: "param1".to_string(), value: restclient::FormParamValue::Text("value1".to_string()) }
+            restclient::FormDataParamSpec { content_type: None, enabled: true, name: "param1".to_string(), value: restclient::FormParamValue::Text("value1".to_string()) }
         ]))
     }
 
@@ -1082,9 +1265,9 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request).unwrap();        
+        let mut res = convert_body(&postman_request, false).unwrap();        
         assert_eq!(res, restclient::Body::FormData(vec![
-            restclient::FormDataParamSpec { content_type: None, name: "param1".to_string(), value: restclient::FormParamValue::Text("value1".to_string()) }
+            restclient::FormDataParamSpec { content_type: None, enabled: true, name: "param1".to_string(), value: restclient::FormParamValue::Text("value1".to_string()) }
         ]))
     }    
 
@@ -1101,7 +1284,7 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
         postman_request.body.as_mut().unwrap().formdata.insert(vec![
                 v2_1_0::FormParameter {
@@ -1110,7 +1293,7 @@ mod tests {
                     ..Default::default()
                 },
             ]);
-        res = convert_body(&postman_request);
+        res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -1130,9 +1313,9 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request).unwrap();        
+        let mut res = convert_body(&postman_request, false).unwrap();        
         assert_eq!(res, restclient::Body::FormData(vec![
-            restclient::FormDataParamSpec { content_type: None, name: "param1".to_string(), value: restclient::FormParamValue::File(vec!["file1".to_string()]) }
+            restclient::FormDataParamSpec { content_type: None, enabled: true, name: "param1".to_string(), value: restclient::FormParamValue::File(vec!["file1".to_string()]) }
         ]))
     }    
 
@@ -1151,9 +1334,9 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request).unwrap();        
+        let mut res = convert_body(&postman_request, false).unwrap();        
         assert_eq!(res, restclient::Body::FormData(vec![
-            restclient::FormDataParamSpec { content_type: None, name: "param1".to_string(), value: restclient::FormParamValue::File(vec!["file1".to_string(), "file2".to_string()]) }
+            restclient::FormDataParamSpec { content_type: None, enabled: true, name: "param1".to_string(), value: restclient::FormParamValue::File(vec!["file1".to_string(), "file2".to_string()]) }
         ]))
     }    
 
@@ -1171,7 +1354,7 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());
     }
 
@@ -1193,7 +1376,7 @@ mod tests {
             ]),
             ..Default::default()
         });
-        let mut res = convert_body(&postman_request);
+        let mut res = convert_body(&postman_request, false);
         assert!(res.is_err());        
     }
 }
@@ -0,0 +1,369 @@
+//! translates postman request chaining into RestClient request variables
+//! Postman chains requests with test scripts like pm.environment.set("token", pm.response.json().access_token), so that requests sent later can use {{token}}.
+//! RestClient has no scripts, but a request can reference the response of a named one: {{login.response.body.$.access_token}}.
+//! Scripts are not run, they are only searched for the usual ways of setting a variable from the response. Anything else is reported.
+//! Only request scripts are searched: folder and collection scripts run after every request, so there is no single request to reference
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+};
+
+use fancy_regex::{Captures, Regex};
+use lazy_static::lazy_static;
+
+use crate::{
+    postman::v2_1_0::{Event, Host},
+    report::{ItemReport, Report},
+    restclient::{HttpRequest, ResponseCapture, ResponseValue},
+};
+
+/// the variables a request's test scripts set from its response
+pub(super) fn response_captures(
+    events: &Option<Vec<Event>>,
+    report: &mut ItemReport,
+) -> Vec<ResponseCapture> {
+    events
+        .iter()
+        .flatten()
+        .filter(|e| e.listen == "test" && e.disabled != Some(true))
+        .filter_map(script_code)
+        .flat_map(|code| captures_in(&code, report))
+        .collect()
+}
+
+/// a response a later request can reference
+struct Reference {
+    /// folders enclosing the request setting the variable, which tell the file it is written to when requests are split by folder
+    folder: Vec<String>,
+    request: String,
+    reference: String,
+}
+
+/// replaces references to variables set by a previous request with references to that request's response
+/// requests must already have their final names. A variable set again by a later request is taken from the latest one, as postman would when running the collection in order
+/// RestClient only sees requests of the same file. When requests are written one file per folder, references to requests in other folders are left as they are and reported
+pub(super) fn chain_requests(
+    requests: &mut [HttpRequest],
+    one_file_per_folder: bool,
+    report: &mut Report,
+) {
+    let mut references: HashMap<String, Reference> = HashMap::new();
+    for request in requests.iter_mut() {
+        if !references.is_empty() {
+            let unreachable = RefCell::new(BTreeSet::new());
+            let folder = request.source().folder.clone();
+            request.rewrite_text(|content| {
+                replace_references(content, |variable| {
+                    let reference = references.get(variable)?;
+                    if one_file_per_folder && reference.folder != folder {
+                        unreachable.borrow_mut().insert(variable.to_string());
+                        return None;
+                    }
+                    Some(reference.reference.clone())
+                })
+            });
+            let mut report = report.for_item(request.source().origin.clone());
+            for variable in unreachable.into_inner() {
+                report.warning(format!(
+                    "{{{{{variable}}}}} is set by the test script of request {}, which is written to another file. RestClient only references requests of the same file, so it is left as it is",
+                    references[&variable].request
+                ));
+            }
+        }
+        for capture in &request.source().captures {
+            references.insert(
+                capture.variable.clone(),
+                Reference {
+                    folder: request.source().folder.clone(),
+                    request: request.name().to_string(),
+                    reference: capture.value.reference(request.name()),
+                },
+            );
+        }
+    }
+}
+
+fn replace_references(content: &str, reference: impl Fn(&str) -> Option<String>) -> String {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r#"\{\{([^{}]+)\}\}"#).unwrap();
+    }
+    RE.replace_all(content, |caps: &Captures| {
+        reference(caps[1].trim()).unwrap_or_else(|| caps[0].to_string())
+    })
+    .to_string()
+}
+
+fn script_code(event: &Event) -> Option<String> {
+    match event.script.as_ref()?.exec.as_ref()? {
+        Host::String(code) => Some(code.clone()),
+        Host::StringArray(lines) => Some(lines.join("\n")),
+    }
+}
+
+/// goes through the script in order, following variables holding (parts of) the response, as in var jsonData = pm.response.json()
+fn captures_in(code: &str, report: &mut ItemReport) -> Vec<ResponseCapture> {
+    lazy_static! {
+        static ref STATEMENT: Regex = Regex::new(
+            r#"\b(?:(?:var|let|const)\s+([A-Za-z_$][\w$]*)\s*=|(?:pm\.(?:environment|collectionVariables|globals)\.set|postman\.set(?:Environment|Global)Variable)\s*\()"#
+        )
+        .unwrap();
+        static ref NAME: Regex = Regex::new(r#"^\s*(["'`])([^"'`]+)\1\s*$"#).unwrap();
+    }
+    let mut aliases = HashMap::new();
+    let mut captures = vec![];
+    for m in STATEMENT.captures_iter(code).flatten() {
+        let whole = m.get(0).unwrap();
+        if is_commented_out(code, whole.start()) {
+            continue;
+        }
+        let rest = &code[whole.end()..];
+        if let Some(alias) = m.get(1) {
+            // a variable declared again with something else no longer holds the response
+            match response_value(expression(rest), &aliases) {
+                Some(value) => aliases.insert(alias.as_str().to_string(), value),
+                None => aliases.remove(alias.as_str()),
+            };
+            continue;
+        }
+        let name = expression(rest);
+        let value = match rest[name.len()..].strip_prefix(',') {
+            Some(value) => expression(value),
+            None => continue,
+        };
+        // variables whose name is computed can not be followed
+        let variable = match NAME.captures(name) {
+            Ok(Some(caps)) => caps[2].to_string(),
+            _ => continue,
+        };
+        match response_value(value, &aliases) {
+            Some(value) => captures.push(ResponseCapture { variable, value }),
+            None => report.warning(format!(
+                "test script sets {variable} from {}, which RestClient can not take from the response. Requests using {variable} will not see this value",
+                value.trim()
+            )),
+        }
+    }
+    captures
+}
+
+fn is_commented_out(code: &str, position: usize) -> bool {
+    let line_start = code[..position].rfind('\n').map_or(0, |i| i + 1);
+    code[line_start..position].trim_start().starts_with("//")
+}
+
+/// the javascript expression code starts with: everything up to the first ; , or line break not nested in brackets nor quotes, or up to the bracket closing an enclosing one
+fn expression(code: &str) -> &str {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in code.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => return &code[..i],
+            ')' | ']' | '}' => depth -= 1,
+            ';' | ',' | '\n' if depth == 0 => return &code[..i],
+            _ => {}
+        }
+    }
+    code
+}
+
+/// what part of the response an expression evaluates to, if any
+/// json bodies can be walked with property and index accessors: pm.response.json().data[0]["access-token"]
+fn response_value(
+    expression: &str,
+    aliases: &HashMap<String, ResponseValue>,
+) -> Option<ResponseValue> {
+    lazy_static! {
+        static ref HEADER: Regex = Regex::new(
+            r#"^(?:pm\.response\.headers\.get|postman\.getResponseHeader)\(\s*(["'`])([^"'`]+)\1\s*\)$"#
+        )
+        .unwrap();
+        static ref TEXT: Regex = Regex::new(r#"^(?:pm\.response\.text\(\)|responseBody)$"#).unwrap();
+        static ref JSON: Regex = Regex::new(
+            r#"^(?:pm\.response\.json\(\)|JSON\.parse\(\s*(?:responseBody|pm\.response\.text\(\))\s*\))"#
+        )
+        .unwrap();
+        static ref IDENTIFIER: Regex = Regex::new(r#"^[A-Za-z_$][\w$]*"#).unwrap();
+        static ref ACCESSOR: Regex = Regex::new(
+            r#"^\s*(?:\.\s*([A-Za-z_$][\w$]*)|\[\s*(\d+)\s*\]|\[\s*(["'`])([^"'`]*)\3\s*\])"#
+        )
+        .unwrap();
+    }
+    let expression = expression.trim();
+    if let Ok(Some(caps)) = HEADER.captures(expression) {
+        return Some(ResponseValue::Header(caps[2].to_string()));
+    }
+    if TEXT.is_match(expression).unwrap_or(false) {
+        return Some(ResponseValue::Body);
+    }
+    let (mut path, mut rest) = if let Ok(Some(json)) = JSON.find(expression) {
+        ("$".to_string(), &expression[json.end()..])
+    } else {
+        let alias = IDENTIFIER.find(expression).ok().flatten()?;
+        let rest = &expression[alias.end()..];
+        match aliases.get(alias.as_str())? {
+            ResponseValue::JsonPath(path) => (path.clone(), rest),
+            // text and headers can only be used as they are
+            value if rest.is_empty() => return Some(value.clone()),
+            _ => return None,
+        }
+    };
+    while !rest.is_empty() {
+        let caps = ACCESSOR.captures(rest).ok().flatten()?;
+        if let Some(property) = caps.get(1).or_else(|| caps.get(4)) {
+            let property = property.as_str();
+            if IDENTIFIER.find(property).ok().flatten().map(|m| m.end()) == Some(property.len()) {
+                path.push_str(&format!(".{property}"));
+            } else {
+                path.push_str(&format!("['{property}']"));
+            }
+        } else {
+            path.push_str(&format!("[{}]", &caps[2]));
+        }
+        rest = &rest[caps.get(0).unwrap().end()..];
+    }
+    Some(ResponseValue::JsonPath(path))
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        report::Report,
+        restclient::{HttpRequestBuilder, Method, RequestSource},
+    };
+
+    fn captures(code: &str) -> (Vec<ResponseCapture>, Report) {
+        let mut report = Report::default();
+        let captures = captures_in(code, &mut report.for_item(Default::default()));
+        (captures, report)
+    }
+
+    fn capture(variable: &str, value: ResponseValue) -> ResponseCapture {
+        ResponseCapture {
+            variable: variable.to_string(),
+            value,
+        }
+    }
+
+    fn json_path(path: &str) -> ResponseValue {
+        ResponseValue::JsonPath(path.to_string())
+    }
+
+    #[test]
+    fn should_find_variables_set_from_the_response() {
+        let (found, report) = captures(
+            r#"
+            pm.environment.set("token", pm.response.json().access_token);
+            pm.collectionVariables.set('userId', pm.response.json().data[0]["user-id"]);
+            pm.globals.set("session", pm.response.headers.get("X-Session"));
+            postman.setEnvironmentVariable("raw", responseBody);
+            // pm.environment.set("old", pm.response.json().old);
+            pm.environment.set("now", Date.now());
+            "#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                capture("token", json_path("$.access_token")),
+                capture("userId", json_path("$.data[0]['user-id']")),
+                capture("session", ResponseValue::Header("X-Session".to_string())),
+                capture("raw", ResponseValue::Body),
+            ]
+        );
+        assert_eq!(report.warnings(), 1);
+        assert!(report.entries[0]
+            .message
+            .contains("sets now from Date.now()"));
+    }
+
+    #[test]
+    fn should_follow_variables_holding_the_response() {
+        let (found, _) = captures(
+            r#"
+            var jsonData = JSON.parse(responseBody);
+            pm.test("has a token", function () {
+                const auth = jsonData.auth;
+                pm.environment.set("token", auth.token);
+                pm.environment.set("first", jsonData.items[0].id);
+            });
+            jsonData = 1;
+            var jsonData = {};
+            pm.environment.set("lost", jsonData.token);
+            "#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                capture("token", json_path("$.auth.token")),
+                capture("first", json_path("$.items[0].id")),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reference_the_latest_request_setting_a_variable() {
+        let request = |name: &str, url: &str, captures: Vec<ResponseCapture>| {
+            let mut request = HttpRequestBuilder::default()
+                .name(name)
+                .method(Method::Get)
+                .url(url)
+                .build()
+                .unwrap();
+            request.set_source(RequestSource {
+                captures,
+                ..Default::default()
+            });
+            request
+        };
+        let mut requests = vec![
+            request("early", "http://host/{{token}}", vec![]),
+            request(
+                "login",
+                "http://host/{{token}}",
+                vec![capture("token", json_path("$.token"))],
+            ),
+            request("use", "http://host/{{ token }}/{{other}}", vec![]),
+            request(
+                "refresh",
+                "http://host",
+                vec![capture(
+                    "token",
+                    ResponseValue::Header("X-Token".to_string()),
+                )],
+            ),
+            request("again", "http://host/{{token}}", vec![]),
+        ];
+        chain_requests(&mut requests, false, &mut Report::default());
+        let urls: Vec<_> = requests
+            .iter()
+            .map(|r| r.to_restclient().lines().nth(3).unwrap().to_string())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "GET http://host/{{token}} http/1.1",
+                "GET http://host/{{token}} http/1.1",
+                "GET http://host/{{login.response.body.$.token}}/{{other}} http/1.1",
+                "GET http://host http/1.1",
+                "GET http://host/{{refresh.response.headers.X-Token}} http/1.1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! translates postman dynamic variables, like {{$guid}} or {{$randomEmail}}, into RestClient system variables
+//! Both tools write them as {{$name}}, but only a few exist in both, sometimes with different names or arguments.
+//! The rest come from the faker library postman embeds: they are reported and, if asked to, replaced with fixed sample values
+
+use std::{cell::RefCell, collections::BTreeSet};
+
+use fancy_regex::{Captures, Regex};
+use lazy_static::lazy_static;
+
+use crate::{config::FakerPolicy, report::ItemReport, restclient::HttpRequest};
+
+/// rewrites the dynamic variables of every part of the request which can reference variables
+/// RestClient own system variables, such as {{$datetime rfc1123}}, have arguments and are left untouched
+pub(super) fn convert_dynamic_variables(
+    request: &mut HttpRequest,
+    policy: FakerPolicy,
+    report: &mut ItemReport,
+) {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r#"\{\{\s*\$([A-Za-z0-9]+)\s*\}\}"#).unwrap();
+    }
+    // faker variables found, each one reported once however many times the request uses it
+    let fakers = RefCell::new(BTreeSet::new());
+    request.rewrite_text(|content| {
+        RE.replace_all(content, |caps: &Captures| {
+            let name = &caps[1];
+            if let Some(system) = system_variable(name) {
+                return system.to_string();
+            }
+            let sample = sample(name);
+            if name.starts_with("random") {
+                fakers.borrow_mut().insert((name.to_string(), sample));
+            }
+            match (policy, sample) {
+                (FakerPolicy::Sample, Some(sample)) => sample.to_string(),
+                _ => caps[0].to_string(),
+            }
+        })
+        .to_string()
+    });
+    for (name, sample) in fakers.into_inner() {
+        match (policy, sample) {
+            (FakerPolicy::Sample, Some(sample)) => report.warning(format!(
+                "{{{{${name}}}}} has no RestClient equivalent, replaced by the fixed sample value {sample}"
+            )),
+            (FakerPolicy::Sample, None) => report.warning(format!(
+                "{{{{${name}}}}} has no RestClient equivalent nor sample value, it is sent as it is"
+            )),
+            (FakerPolicy::Keep, _) => report.warning(format!(
+                "{{{{${name}}}}} has no RestClient equivalent, it is sent as it is"
+            )),
+        }
+    }
+}
+
+/// the RestClient system variable generating the same kind of value
+fn system_variable(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "guid" | "randomUUID" => "{{$guid}}",
+        "timestamp" => "{{$timestamp}}",
+        "isoTimestamp" => "{{$datetime iso8601}}",
+        // postman includes 1000, RestClient excludes the maximum
+        "randomInt" => "{{$randomInt 0 1001}}",
+        _ => return None,
+    })
+}
+
+/// a plausible value for a faker variable. Always the same, so that converting a collection twice gives the same file
+fn sample(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "randomAbbreviation" => "SQL",
+        "randomAbstractImage" | "randomAnimalsImage" | "randomImageUrl" => {
+            "http://placeimg.com/640/480"
+        }
+        "randomAlphaNumeric" => "a",
+        "randomBankAccount" => "12345678",
+        "randomBankAccountIban" => "DE89370400440532013000",
+        "randomBoolean" => "true",
+        "randomBs" => "synergize scalable supply-chains",
+        "randomCatchPhrase" => "Fully-configurable neutral toolset",
+        "randomCity" => "Springfield",
+        "randomColor" => "blue",
+        "randomCompanyName" => "Acme Inc",
+        "randomCountry" => "Brazil",
+        "randomCountryCode" => "BR",
+        "randomCurrencyCode" => "USD",
+        "randomDateFuture" | "randomDatePast" | "randomDateRecent" => {
+            "Mon Jan 01 2024 00:00:00 GMT+0000 (Coordinated Universal Time)"
+        }
+        "randomDomainName" => "example.com",
+        "randomEmail" | "randomExampleEmail" => "jane.doe@example.com",
+        "randomFileName" => "report.pdf",
+        "randomFirstName" => "Jane",
+        "randomFullName" => "Jane Doe",
+        "randomHexColor" => "#1f77b4",
+        "randomIP" => "192.0.2.1",
+        "randomIPV6" => "2001:db8::1",
+        "randomJobTitle" => "Software Engineer",
+        "randomLastName" => "Doe",
+        "randomLatitude" => "-23.5505",
+        "randomLongitude" => "-46.6333",
+        "randomLoremParagraph" => {
+            "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore."
+        }
+        "randomLoremSentence" => "Lorem ipsum dolor sit amet.",
+        "randomLoremWord" | "randomWord" => "lorem",
+        "randomLoremWords" | "randomWords" => "lorem ipsum dolor",
+        "randomMACAddress" => "00:00:5e:00:53:af",
+        "randomMimeType" => "application/json",
+        "randomNamePrefix" => "Ms.",
+        "randomNameSuffix" => "Jr.",
+        "randomPassword" => "Passw0rd!2024",
+        "randomPhoneNumber" => "555-0100",
+        "randomPrice" => "99.99",
+        "randomProductName" => "Ergonomic Steel Chair",
+        "randomProtocol" => "https",
+        "randomSemver" => "1.0.0",
+        "randomStreetAddress" => "742 Evergreen Terrace",
+        "randomStreetName" => "Evergreen Terrace",
+        "randomUrl" => "https://example.com",
+        "randomUserAgent" => "Mozilla/5.0 (X11; Linux x86_64)",
+        "randomUserName" => "jane.doe",
+        "randomZipCode" => "12345",
+        _ => return None,
+    })
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        report::Report,
+        restclient::{Body, Header, HttpRequestBuilder, Method},
+    };
+
+    const URL: &str = "http://host/{{$guid}}?at={{$timestamp}}";
+
+    fn convert(url: &str, policy: FakerPolicy) -> (String, Report) {
+        let mut request = HttpRequestBuilder::default()
+            .name("r")
+            .method(Method::Post)
+            .url(url)
+            .headers(vec![Header {
+                enabled: true,
+                name: "X-Request-Id".to_string(),
+                value: "{{ $randomUUID }}".to_string(),
+            }])
+            .body(Body::Raw(
+                r#"{"n": {{$randomInt}}, "at": "{{$isoTimestamp}}", "email": "{{$randomEmail}}", "other": "{{$randomEmail}}", "x": "{{$randomUnknown}}", "d": "{{$datetime rfc1123}}"}"#
+                    .to_string(),
+            ))
+            .build()
+            .unwrap();
+        let mut report = Report::default();
+        convert_dynamic_variables(
+            &mut request,
+            policy,
+            &mut report.for_item(Default::default()),
+        );
+        (request.to_restclient(), report)
+    }
+
+    #[test]
+    fn should_translate_variables_restclient_also_has() {
+        let (http, report) = convert(URL, FakerPolicy::Keep);
+        assert_eq!(
+            http,
+            "#####\n# @name r\n\n\
+             POST http://host/{{$guid}}\n    ?at={{$timestamp}} http/1.1\n\
+             X-Request-Id: {{$guid}}\n\n\
+             {\"n\": {{$randomInt 0 1001}}, \"at\": \"{{$datetime iso8601}}\", \"email\": \"{{$randomEmail}}\", \"other\": \"{{$randomEmail}}\", \"x\": \"{{$randomUnknown}}\", \"d\": \"{{$datetime rfc1123}}\"}\n"
+        );
+        let messages: Vec<_> = report.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "{{$randomEmail}} has no RestClient equivalent, it is sent as it is",
+                "{{$randomUnknown}} has no RestClient equivalent, it is sent as it is",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_replace_faker_variables_with_samples_if_asked_to() {
+        let (http, report) = convert(URL, FakerPolicy::Sample);
+        assert!(
+            http.contains(r#""email": "jane.doe@example.com", "other": "jane.doe@example.com""#)
+        );
+        assert!(http.contains(r#""x": "{{$randomUnknown}}""#));
+        assert_eq!(report.warnings(), 2);
+        assert_eq!(
+            report.entries[0].message,
+            "{{$randomEmail}} has no RestClient equivalent, replaced by the fixed sample value jane.doe@example.com"
+        );
+    }
+
+    #[test]
+    fn should_handle_variables_with_digits_in_their_names() {
+        let messages = |report: &Report| {
+            report
+                .entries
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+        };
+        let (http, report) = convert("http://host/ip/{{$randomIPV6}}", FakerPolicy::Keep);
+        assert!(http.contains("POST http://host/ip/{{$randomIPV6}} http/1.1"));
+        assert!(messages(&report).contains(
+            &"{{$randomIPV6}} has no RestClient equivalent, it is sent as it is".to_string()
+        ));
+        let (http, report) = convert("http://host/ip/{{$randomIPV6}}", FakerPolicy::Sample);
+        assert!(http.contains("POST http://host/ip/2001:db8::1 http/1.1"));
+        assert!(messages(&report).contains(
+            &"{{$randomIPV6}} has no RestClient equivalent, replaced by the fixed sample value 2001:db8::1"
+                .to_string()
+        ));
+    }
+}
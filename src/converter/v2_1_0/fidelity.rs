@@ -0,0 +1,157 @@
+//! finds what postman items declare that RestClient can not do, so that it is reported instead of silently lost
+//! Unsupported auth types are not found here: they are dropped where auth is applied, which is the only place knowing what a request inherits
+
+use crate::{
+    postman::v2_1_0::{self, Event, Host},
+    report::{Feature, ItemReport},
+};
+
+/// records the features of a request or folder which are left out of the conversion
+pub(super) fn record_dropped_features(item: &v2_1_0::Items, report: &mut ItemReport) {
+    for (feature, reason) in &item.upgrade_losses {
+        report.dropped(*feature, reason);
+    }
+    record_dropped_scripts(&item.event, report);
+    if item.protocol_profile_behavior.is_some() {
+        report.dropped(
+            Feature::ProtocolProfileBehavior,
+            "RestClient has no per request settings",
+        );
+    }
+    let responses = item.response.iter().flatten().flatten().count();
+    if responses > 0 {
+        report.dropped(
+            Feature::SavedResponses,
+            format!("{responses} saved response(s) not kept, http files have no examples"),
+        );
+    }
+    let request = match &item.request {
+        Some(v2_1_0::RequestUnion::RequestClass(r)) => r,
+        _ => return,
+    };
+    if request.certificate.is_some() {
+        report.dropped(
+            Feature::Certificate,
+            "client certificates are configured in vscode settings (rest-client.certificates)",
+        );
+    }
+    if matches!(&request.proxy, Some(p) if p.disabled != Some(true)) {
+        report.dropped(
+            Feature::Proxy,
+            "RestClient uses the proxy configured in vscode (http.proxy)",
+        );
+    }
+}
+
+/// records scripts which would run when the request, or any request inside the folder or collection, is sent
+/// disabled and empty scripts do nothing in postman either, so nothing is lost with them
+pub(super) fn record_dropped_scripts(events: &Option<Vec<Event>>, report: &mut ItemReport) {
+    for event in events.iter().flatten() {
+        if event.disabled == Some(true) || !has_code(event) {
+            continue;
+        }
+        let feature = match event.listen.as_str() {
+            "prerequest" => Feature::PreRequestScript,
+            "test" => Feature::TestScript,
+            _ => continue,
+        };
+        report.dropped(feature, "RestClient does not run scripts");
+    }
+}
+
+fn has_code(event: &Event) -> bool {
+    match event.script.as_ref().and_then(|s| s.exec.as_ref()) {
+        Some(Host::String(code)) => !code.trim().is_empty(),
+        Some(Host::StringArray(lines)) => lines.iter().any(|l| !l.trim().is_empty()),
+        None => false,
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{postman::v1, report::Report};
+
+    fn dropped(item: serde_json::Value) -> Vec<Feature> {
+        let item: v2_1_0::Items = serde_json::from_value(item).unwrap();
+        let mut report = Report::default();
+        record_dropped_features(&item, &mut report.for_item(Default::default()));
+        report.dropped.into_iter().map(|d| d.feature).collect()
+    }
+
+    #[test]
+    fn should_record_scripts_settings_responses_certificates_and_proxies() {
+        let features = dropped(json!({
+            "name": "r",
+            "event": [
+                { "listen": "prerequest", "script": { "exec": ["pm.variables.set('a', 1)"] } },
+                { "listen": "test", "script": { "exec": "pm.test('ok')" } }
+            ],
+            "protocolProfileBehavior": { "disableBodyPruning": true },
+            "response": [{ "name": "example" }],
+            "request": {
+                "method": "GET",
+                "url": "http://host",
+                "certificate": { "name": "cert" },
+                "proxy": { "host": "proxy" }
+            }
+        }));
+        assert_eq!(
+            features,
+            vec![
+                Feature::PreRequestScript,
+                Feature::TestScript,
+                Feature::ProtocolProfileBehavior,
+                Feature::SavedResponses,
+                Feature::Certificate,
+                Feature::Proxy,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_record_what_postman_would_not_use_either() {
+        let features = dropped(json!({
+            "name": "r",
+            "event": [
+                { "listen": "prerequest", "script": { "exec": ["pm.variables.set('a', 1)"] }, "disabled": true },
+                { "listen": "test", "script": { "exec": ["", "  "] } }
+            ],
+            "response": [],
+            "request": {
+                "method": "GET",
+                "url": "http://host",
+                "proxy": { "host": "proxy", "disabled": true }
+            }
+        }));
+        assert!(features.is_empty());
+    }
+
+    #[test]
+    fn should_record_what_upgrading_from_v1_left_out() {
+        let request: v1::Request = serde_json::from_value(json!({
+            "id": "r",
+            "name": "upload",
+            "url": "http://host/upload",
+            "method": "PUT",
+            "dataMode": "binary"
+        }))
+        .unwrap();
+        let mut report = Report::default();
+        record_dropped_features(
+            &v2_1_0::Items::from(&request),
+            &mut report.for_item(Default::default()),
+        );
+        assert_eq!(report.dropped.len(), 1);
+        assert_eq!(report.dropped[0].feature, Feature::BinaryBody);
+        assert_eq!(
+            report.dropped[0].reason,
+            "v1 collections do not record the file sent as binary body"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! checks graphql bodies and lays out their variables
+//! Postman sends queries and variables as they are typed, so mistakes only show up when the server answers.
+//! Variables and queries usually reference postman variables, as in {"id": {{userId}}}, which are not valid json nor graphql until postman replaces them.
+//! They are replaced by null before anything is parsed
+
+use fancy_regex::Regex;
+use graphql_parser::query::{parse_query, Definition, OperationDefinition};
+use lazy_static::lazy_static;
+
+use crate::{report::ItemReport, restclient::GraphqlSpec};
+
+const INDENT: &str = "  ";
+
+/// the variables laid out one value per line, keeping the order they were written in
+/// fails with the reason if they are not a json object
+pub(super) fn pretty_variables(variables: &str) -> Result<String, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(&without_references(variables)).map_err(|e| e.to_string())?;
+    if !value.is_object() {
+        return Err("graphql variables must be a json object".to_string());
+    }
+    Ok(pretty_json(variables))
+}
+
+/// reports variables which are not a json object and, if asked to, queries which do not parse
+/// requests with an operation name are sent as json documents, which invalid variables would break. They are sent as graphql requests instead, without their operation name
+pub(super) fn check_graphql(spec: &mut GraphqlSpec, validate_query: bool, report: &mut ItemReport) {
+    if let Some(Err(e)) = spec.variables.as_deref().map(pretty_variables) {
+        match spec.operation_name.take() {
+            Some(operation_name) => report.warning(format!(
+                "graphql variables are not valid, they are kept as they are and the request is sent without its operation name {operation_name}. Caused by {e}"
+            )),
+            None => report.warning(format!(
+                "graphql variables are not valid, they are kept as they are. Caused by {e}"
+            )),
+        }
+    }
+    if !validate_query {
+        return;
+    }
+    let query = without_references(&spec.spec);
+    let document = match parse_query::<String>(&query) {
+        Ok(document) => document,
+        Err(e) => {
+            report.warning(format!(
+                "graphql query is not valid. Caused by {}",
+                e.to_string().trim_end()
+            ));
+            return;
+        }
+    };
+    if let Some(operation_name) = &spec.operation_name {
+        let found = document.definitions.iter().any(|d| match d {
+            Definition::Operation(OperationDefinition::Query(q)) => {
+                q.name.as_ref() == Some(operation_name)
+            }
+            Definition::Operation(OperationDefinition::Mutation(m)) => {
+                m.name.as_ref() == Some(operation_name)
+            }
+            Definition::Operation(OperationDefinition::Subscription(s)) => {
+                s.name.as_ref() == Some(operation_name)
+            }
+            _ => false,
+        });
+        if !found {
+            report.warning(format!(
+                "graphql query has no operation named {operation_name}"
+            ));
+        }
+    }
+}
+
+fn without_references(text: &str) -> String {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r#"\{\{[^{}]+\}\}"#).unwrap();
+    }
+    RE.replace_all(text, "null").to_string()
+}
+
+/// indents json text already known to be valid, once references are left aside
+/// serde_json would sort object keys, and variables are easier to read in the order their author chose
+fn pretty_json(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut pretty = String::new();
+    let mut depth = 0;
+    let mut i = 0;
+    let new_line = |pretty: &mut String, depth: usize| {
+        pretty.push('\n');
+        pretty.push_str(&INDENT.repeat(depth));
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                // strings are copied as they are, escapes included
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                pretty.extend(&chars[start..(i + 1).min(chars.len())]);
+            }
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                // a reference, copied as it is
+                let start = i;
+                while i + 1 < chars.len() && !(chars[i] == '}' && chars[i + 1] == '}') {
+                    i += 1;
+                }
+                i += 1;
+                pretty.extend(&chars[start..(i + 1).min(chars.len())]);
+            }
+            '{' | '[' => {
+                let close = if c == '{' { '}' } else { ']' };
+                match chars[i + 1..].iter().position(|c| !c.is_whitespace()) {
+                    Some(next) if chars[i + 1 + next] == close => {
+                        pretty.push(c);
+                        pretty.push(close);
+                        i += next + 1;
+                    }
+                    _ => {
+                        pretty.push(c);
+                        depth += 1;
+                        new_line(&mut pretty, depth);
+                    }
+                }
+            }
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                new_line(&mut pretty, depth);
+                pretty.push(c);
+            }
+            ',' => {
+                pretty.push(c);
+                new_line(&mut pretty, depth);
+            }
+            ':' => pretty.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => pretty.push(c),
+        }
+        i += 1;
+    }
+    pretty
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        report::Report,
+        restclient::{Body, HttpRequestBuilder, Method},
+    };
+
+    fn warnings(mut spec: GraphqlSpec, validate_query: bool) -> Vec<String> {
+        let mut report = Report::default();
+        check_graphql(
+            &mut spec,
+            validate_query,
+            &mut report.for_item(Default::default()),
+        );
+        report.entries.into_iter().map(|e| e.message).collect()
+    }
+
+    fn spec(query: &str, variables: Option<&str>, operation_name: Option<&str>) -> GraphqlSpec {
+        GraphqlSpec {
+            spec: query.to_string(),
+            variables: variables.map(str::to_string),
+            operation_name: operation_name.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn should_lay_out_variables_in_their_original_order() {
+        assert_eq!(
+            pretty_variables(r#"{"z": 1, "a": {"id": {{userId}}, "tags": ["x", "y"], "empty": {}}, "s": "{{name}}, \"q\""}"#)
+                .unwrap(),
+            "{\n  \"z\": 1,\n  \"a\": {\n    \"id\": {{userId}},\n    \"tags\": [\n      \"x\",\n      \"y\"\n    ],\n    \"empty\": {}\n  },\n  \"s\": \"{{name}}, \\\"q\\\"\"\n}"
+        );
+        assert!(pretty_variables("[1]").is_err());
+        assert!(pretty_variables("{\"a\": }").is_err());
+    }
+
+    #[test]
+    fn should_report_invalid_variables_and_queries() {
+        assert!(warnings(spec("query { a", Some("{}"), None), false).is_empty());
+        let found = warnings(spec("query { a", Some("a: 1"), None), true);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].starts_with("graphql variables are not valid"));
+        assert!(found[1].starts_with("graphql query is not valid"));
+        assert!(warnings(
+            spec(
+                "query User($id: ID!) { user(id: $id, at: {{now}}) { name } }",
+                Some(r#"{"id": "{{id}}"}"#),
+                Some("User")
+            ),
+            true
+        )
+        .is_empty());
+        assert_eq!(
+            warnings(spec("query User { a }", None, Some("Order")), true),
+            vec!["graphql query has no operation named Order"]
+        );
+    }
+
+    #[test]
+    fn should_send_requests_with_invalid_variables_without_their_operation_name() {
+        let mut spec = spec("query User { a }", Some("{\"id\": }"), Some("User"));
+        let mut report = Report::default();
+        check_graphql(&mut spec, true, &mut report.for_item(Default::default()));
+        assert_eq!(spec.operation_name, None);
+        assert_eq!(spec.variables.as_deref(), Some("{\"id\": }"));
+        assert_eq!(report.warnings(), 1);
+        assert!(report.entries[0]
+            .message
+            .starts_with("graphql variables are not valid, they are kept as they are and the request is sent without its operation name User."));
+        let request = HttpRequestBuilder::default()
+            .name("r")
+            .method(Method::Post)
+            .url("http://host/graphql")
+            .body(Body::Graphql(spec))
+            .build()
+            .unwrap();
+        let http = request.to_restclient();
+        assert!(http.contains("X-REQUEST-TYPE: GraphQL"));
+        assert!(!http.contains("operationName"));
+    }
+}
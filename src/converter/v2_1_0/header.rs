@@ -1,14 +1,18 @@
 use crate::{error::PostresError, postman::v2_1_0, restclient};
 
+/// disabled headers are left out, unless keep_disabled is set
 pub(crate) fn convert_headers(
     postman_req: &v2_1_0::RequestClass,
+    keep_disabled: bool,
 ) -> Result<Vec<restclient::Header>, PostresError> {
     if postman_req.header.is_none() {
         return Ok(vec![]);
     }
     let postman_headers = postman_req.header.as_ref().unwrap();
     match postman_headers {
-        v2_1_0::HeaderUnion::HeaderArray(headers) => http_header_from_header_array(headers),
+        v2_1_0::HeaderUnion::HeaderArray(headers) => {
+            http_header_from_header_array(headers, keep_disabled)
+        }
         v2_1_0::HeaderUnion::String(header) => http_header_from_string(header),
     }
 }
@@ -31,6 +35,7 @@ fn http_header_from_string(header: &str) -> Result<Vec<restclient::Header>, Post
     let value = h[1].trim_start();
 
     Ok(vec![restclient::Header {
+        enabled: true,
         name: name.to_string(),
         value: value.to_string(),
     }])
@@ -38,13 +43,17 @@ fn http_header_from_string(header: &str) -> Result<Vec<restclient::Header>, Post
 
 fn http_header_from_header_array(
     headers: &Vec<v2_1_0::Header>,
+    keep_disabled: bool,
 ) -> Result<Vec<restclient::Header>, PostresError> {
     Ok(headers
         .iter()
         .filter(|header| {
-            matches!(header.disabled, None) || matches!(header.disabled, Some(d) if !d)
+            keep_disabled
+                || matches!(header.disabled, None)
+                || matches!(header.disabled, Some(d) if !d)
         })
         .map(|header| restclient::Header {
+            enabled: header.disabled != Some(true),
             name: header.key.clone(),
             value: header.value.clone(),
         })
@@ -62,7 +71,7 @@ mod tests {
     #[test]
     fn should_return_empty_list_of_headers_if_postman_headers_are_not_present() {
         let mut req = default_postman_request_class();
-        assert_eq!(convert_headers(&req).unwrap(), vec![]);
+        assert_eq!(convert_headers(&req, false).unwrap(), vec![]);
     }
 
     #[test]
@@ -72,6 +81,7 @@ mod tests {
         assert_eq!(
             http_header_from_string("name: value").unwrap(),
             vec![Header {
+                enabled: true,
                 name: "name".to_string(),
                 value: "value".to_string()
             }]
@@ -80,6 +90,7 @@ mod tests {
         assert_eq!(
             http_header_from_string("name: value:aaa,bbbc").unwrap(),
             vec![Header {
+                enabled: true,
                 name: "name".to_string(),
                 value: "value:aaa,bbbc".to_string()
             }]
@@ -116,19 +127,24 @@ mod tests {
                 value: "should not appear".to_string(),
             },
         ];
-        let converted = http_header_from_header_array(&postman_headers).unwrap();
+        let converted = http_header_from_header_array(&postman_headers, false).unwrap();
         assert_eq!(
             converted,
             vec![
                 Header {
+                    enabled: true,
                     name: "name1".to_string(),
                     value: "value1".to_string(),
                 },
                 Header {
+                    enabled: true,
                     name: "name2".to_string(),
                     value: "value2".to_string(),
                 },
             ],
-        )
+        );
+        let converted = http_header_from_header_array(&postman_headers, true).unwrap();
+        assert_eq!(converted.len(), 3);
+        assert!(!converted[2].enabled);
     }
 }
@@ -11,8 +11,14 @@ pub(crate) fn convert_method(
         .method
         .as_ref()
         .ok_or(PostresError::PostmanRequestMethodNotPresent)?;
-    let postman_method = PostmanMethod::from_str(&method)
-        .map_err(|e| PostresError::invalid_postman_method(&method))?;
+    let postman_method = match PostmanMethod::from_str(method) {
+        Ok(m) => m,
+        // postman lets users type any method they want. As long as it is a valid http method name, it goes as it was typed
+        Err(_) => {
+            return restclient::Method::extension(method)
+                .ok_or_else(|| PostresError::invalid_postman_method(method))
+        }
+    };
 
     /*
         def 014: traits and how rust approaches common problems solved by inheritance and polimorphism in object oriented languages
@@ -135,7 +141,8 @@ pub(crate) fn convert_method(
 #[derive(Debug, EnumString)]
 // specify that the macro should expect values on the original string in uppercase that matches our enum variants which have, by rust conventions, in camel case with the first letter captalized
 // if we didn't do it, the matching would fail, because by default matching is performed against the variants as they are specified
-#[strum(serialize_all = "UPPERCASE")]
+// postman does not force methods to be typed in uppercase, so get is also matched as GET
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
 enum PostmanMethod {
     Copy,
     Delete,
@@ -520,6 +527,29 @@ mod tests {
         assert_eq!(convert_method(&req).unwrap(), Method::Put);
         req.method = Some("DELETE".to_string());
         assert_eq!(convert_method(&req).unwrap(), Method::Delete);
+        req.method = Some("get".to_string());
+        assert_eq!(convert_method(&req).unwrap(), Method::Get);
+    }
+
+    #[test]
+    fn should_convert_extension_method_keeping_its_spelling() {
+        use restclient::Method;
+        let mut req = default_postman_request_class();
+        for name in ["SEARCH", "REPORT", "MKCOL", "QUERY", "MkCalendar"] {
+            req.method = Some(name.to_string());
+            let converted = convert_method(&req).unwrap();
+            assert_eq!(converted, Method::Extension(name.to_string()));
+            assert_eq!(converted.to_string(), name);
+        }
+    }
+
+    #[test]
+    fn should_reject_method_names_which_are_not_tokens() {
+        let mut req = default_postman_request_class();
+        for name in ["", "GET\n", "GET/1", "(GET)", "GÉT"] {
+            req.method = Some(name.to_string());
+            assert!(convert_method(&req).is_err(), "{name} should be rejected");
+        }
     }
 
     #[cfg(test)]
@@ -531,7 +561,8 @@ mod tests {
         #[should_panic(expected = "InvalidPostmanMethod")]
         fn should_fail_to_convert_when_postman_method_is_unknown() {
             let mut req = default_postman_request_class();
-            req.method = Some("INVALID".to_string());
+            // spaces are not allowed in method names
+            req.method = Some("GET ME".to_string());
             convert_method(&req).unwrap();
         }
     }
@@ -1,19 +1,29 @@
-use crate::{error::PostresError, postman::v2_1_0, restclient};
+use crate::{
+    config::ConversionOptions, error::PostresError, postman::v2_1_0, report::ItemReport, restclient,
+};
 
 use super::{
-    extract_path_variables, header::convert_headers, method::convert_method, url::convert_url,
+    body::{content_type, convert_body},
+    graphql::check_graphql,
+    header::convert_headers,
+    method::convert_method,
+    url::{convert_raw_url, convert_url, disabled_query_params},
 };
 
 pub(crate) fn convert_request(
     name: &str,
     item: &v2_1_0::Items,
+    options: &ConversionOptions,
+    report: &mut ItemReport,
 ) -> Result<restclient::HttpRequest, PostresError> {
     let postman_request = item
         .request
         .as_ref()
-        .ok_or_else(|| PostresError::postman_request_not_present(""))?;
+        .ok_or_else(|| PostresError::postman_request_not_present(name))?;
     match postman_request {
-        v2_1_0::RequestUnion::RequestClass(r) => http_request_from_request_class(name, r),
+        v2_1_0::RequestUnion::RequestClass(r) => {
+            http_request_from_request_class(name, r, options, report)
+        }
         v2_1_0::RequestUnion::String(r) => http_request_from_string(&name, &r),
     }
 }
@@ -23,7 +33,7 @@ fn http_request_from_string(
     req: &str,
 ) -> Result<restclient::HttpRequest, PostresError> {
     // per postman specification, a single string request is a Get request with the string as url
-    let req = extract_path_variables(req);
+    let req = convert_raw_url(req);
     Ok(restclient::HttpRequestBuilder::default()
         .name(name.to_string())
         .method(restclient::Method::Get)
@@ -34,15 +44,40 @@ fn http_request_from_string(
 fn http_request_from_request_class(
     name: &str,
     postman_req: &v2_1_0::RequestClass,
+    options: &ConversionOptions,
+    report: &mut ItemReport,
 ) -> Result<restclient::HttpRequest, PostresError> {
     let mut request_builder = restclient::HttpRequestBuilder::default();
-    let postman_url = convert_url(postman_req)?;
+    request_builder.name(name);
+    // query parameters are part of the url here. They are extracted into the request query params by the builder post build function (see 012)
+    let postman_url = convert_url(postman_req, options.url_conflict, report)?;
     request_builder.url(postman_url);
     let method = convert_method(postman_req)?;
     request_builder.method(method);
-
-    let headers = convert_headers(postman_req)?;
-    Ok(restclient::HttpRequestBuilder::default().build()?)
+    let headers = convert_headers(postman_req, options.keep_disabled)?;
+    request_builder.headers(headers);
+    let mut body = convert_body(postman_req, options.keep_disabled)?;
+    if let restclient::Body::Graphql(spec) = &mut body {
+        check_graphql(spec, options.validate_graphql, report);
+    }
+    let content_type = content_type(postman_req, &body);
+    request_builder.body(body);
+    let mut request = request_builder.build()?;
+    // the url only holds what is sent, so disabled query parameters come after the enabled ones
+    if options.keep_disabled {
+        for param in disabled_query_params(postman_req) {
+            request.add_query_param(param);
+        }
+    }
+    // postman sends a content type matching the body, unless the user sets one
+    if let Some(content_type) = content_type {
+        request.add_header_if_absent(restclient::Header {
+            enabled: true,
+            name: "Content-Type".to_string(),
+            value: content_type.to_string(),
+        });
+    }
+    Ok(request)
 }
 
 /*
@@ -53,6 +88,7 @@ mod tests {
 
     use super::super::tests::*;
     use super::*;
+    use crate::report::Report;
 
     #[test]
     fn should_convert_request_from_string_handling_path_variable() {
@@ -128,10 +164,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_convert_request_from_request_class() {
+        let mut req = default_postman_request_class();
+        req.method = Some("POST".to_string());
+        req.url = Some(v2_1_0::Url::String(
+            "http://127.0.0.1:3000/a/:b?aaa=111".to_string(),
+        ));
+        req.header = Some(v2_1_0::HeaderUnion::String(
+            "Content-Type: application/json".to_string(),
+        ));
+        req.body = Some(v2_1_0::Body {
+            mode: Some(v2_1_0::Mode::Raw),
+            raw: Some("{}".to_string()),
+            ..Default::default()
+        });
+        let converted = http_request_from_request_class(
+            "testReq",
+            &req,
+            &ConversionOptions::default(),
+            &mut Report::default().for_item(Default::default()),
+        )
+        .unwrap();
+        assert_eq!(
+            converted,
+            restclient::HttpRequestBuilder::default()
+                .method(restclient::Method::Post)
+                .name("testReq")
+                .url("http://127.0.0.1:3000/a/{{b}}")
+                .query_params(vec![restclient::QueryParam::new("aaa", "111")])
+                .headers(vec![restclient::Header {
+                    enabled: true,
+                    name: "Content-Type".to_string(),
+                    value: "application/json".to_string(),
+                }])
+                .body(restclient::Body::Raw("{}".to_string()))
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "PostmanRequestMethodNotPresent")]
+    fn should_fail_to_convert_request_class_without_method() {
+        let mut req = default_postman_request_class();
+        req.url = Some(v2_1_0::Url::String("http://127.0.0.1:3000".to_string()));
+        http_request_from_request_class(
+            "testReq",
+            &req,
+            &ConversionOptions::default(),
+            &mut Report::default().for_item(Default::default()),
+        )
+        .unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "PostManRequestNotPresent { path: \"\" }")]
     fn should_fail_to_convert_request_if_request_cannot_be_found() {
         let req = default_postman_items();
-        convert_request("", &req).unwrap();
+        convert_request(
+            "",
+            &req,
+            &ConversionOptions::default(),
+            &mut Report::default().for_item(Default::default()),
+        )
+        .unwrap();
     }
 }
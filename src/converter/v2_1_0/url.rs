@@ -1,20 +1,324 @@
-use crate::{error::PostresError, postman::v2_1_0};
+use crate::{
+    config::UrlConflictPolicy,
+    error::PostresError,
+    postman::v2_1_0,
+    report::ItemReport,
+    restclient::{QueryParam, TemplatedUrl},
+};
 
 use super::extract_path_variables;
 
-pub(crate) fn convert_url(postman_req: &v2_1_0::RequestClass) -> Result<String, PostresError> {
+pub(crate) fn convert_url(
+    postman_req: &v2_1_0::RequestClass,
+    conflict_policy: UrlConflictPolicy,
+    report: &mut ItemReport,
+) -> Result<String, PostresError> {
     let postman_url = postman_req
         .url
         .as_ref()
         .ok_or(PostresError::PostmanRequestUrlNotPresent)?;
     let res = match postman_url {
-        v2_1_0::Url::String(url) => url,
-        v2_1_0::Url::UrlClass(c) => {
-            // per postman specification, the raw field should contain the complete url
-            c.raw
-                .as_ref()
-                .ok_or(PostresError::PostmanRequestUrlNotPresent)?
+        v2_1_0::Url::String(url) => url.clone(),
+        v2_1_0::Url::UrlClass(c) => url_from_url_class(c, conflict_policy, report)?,
+    };
+    Ok(convert_raw_url(&res))
+}
+
+/// default values of the path variables of a request, as postman stores them next to the structured url
+pub(crate) fn path_variables(item: &v2_1_0::Items) -> &Option<Vec<v2_1_0::Variable>> {
+    match &item.request {
+        Some(v2_1_0::RequestUnion::RequestClass(v2_1_0::RequestClass {
+            url: Some(v2_1_0::Url::UrlClass(url)),
+            ..
+        })) => &url.variable,
+        _ => &None,
+    }
+}
+
+/// query parameters postman shows, but does not send. Only structured urls have them, raw urls hold what is sent
+pub(crate) fn disabled_query_params(postman_req: &v2_1_0::RequestClass) -> Vec<QueryParam> {
+    let query = match &postman_req.url {
+        Some(v2_1_0::Url::UrlClass(url)) => &url.query,
+        _ => return vec![],
+    };
+    query
+        .iter()
+        .flatten()
+        .filter(|q| q.disabled == Some(true) && q.key.is_some())
+        .map(|q| {
+            QueryParam::disabled(
+                q.key.as_deref().unwrap_or_default(),
+                q.value.as_deref().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// converts an url written as text, as found in string requests or in the raw field of structured urls
+/// only the path can hold path variables. Looking for them elsewhere would take a :port or something like ?time=10:30 as a variable
+pub(crate) fn convert_raw_url(raw: &str) -> String {
+    let mut url = TemplatedUrl::parse(raw);
+    url.path = extract_path_variables(&url.path);
+    url.to_string()
+}
+
+// per postman specification, the raw field should contain the complete url, and the other fields should contain its parts
+// either might be missing, and when both are present they might disagree
+fn url_from_url_class(
+    url: &v2_1_0::UrlClass,
+    conflict_policy: UrlConflictPolicy,
+    report: &mut ItemReport,
+) -> Result<String, PostresError> {
+    let raw = url.raw.as_ref().filter(|r| !r.trim().is_empty());
+    let structured = structured_url(url);
+    match (raw, structured) {
+        (None, None) => Err(PostresError::PostmanRequestUrlNotPresent),
+        (Some(raw), None) => Ok(raw.clone()),
+        (None, Some(structured)) => Ok(structured.to_string()),
+        (Some(raw), Some(structured)) if same_url(&TemplatedUrl::parse(raw), &structured) => {
+            Ok(raw.clone())
         }
+        (Some(raw), Some(structured)) => match conflict_policy {
+            UrlConflictPolicy::PreferRaw => Ok(raw.clone()),
+            UrlConflictPolicy::PreferStructured => Ok(structured.to_string()),
+            UrlConflictPolicy::Warn => {
+                report.warning(format!("url {raw} does not match its parts, which describe {structured}. Using {structured}"));
+                Ok(structured.to_string())
+            }
+        },
+    }
+}
+
+/// whether both urls reach the same resource, even if written differently
+/// scheme and host are compared ignoring case, unless they hold variables, an empty path is the root path and the query is compared by its parameters, so that a trailing & makes no difference
+fn same_url(a: &TemplatedUrl, b: &TemplatedUrl) -> bool {
+    let case_insensitive = |part: &Option<String>| {
+        part.as_ref().map(|p| match p.contains("{{") {
+            true => p.clone(),
+            false => p.to_ascii_lowercase(),
+        })
     };
-    Ok(extract_path_variables(res))
+    let path = |url: &TemplatedUrl| match url.path.as_str() {
+        "" => "/".to_string(),
+        p => p.to_string(),
+    };
+    case_insensitive(&a.scheme) == case_insensitive(&b.scheme)
+        && case_insensitive(&a.host) == case_insensitive(&b.host)
+        && a.port == b.port
+        && path(a) == path(b)
+        && a.query_params() == b.query_params()
+        && a.fragment == b.fragment
+}
+
+/// builds the url described by the protocol, host, port, path, query and hash fields
+/// returns None if there is neither host nor path, in which case there is nothing to build an url from
+fn structured_url(url: &v2_1_0::UrlClass) -> Option<TemplatedUrl> {
+    // the host might come split by its dots
+    let host = url.host.as_ref().map(|h| match h {
+        v2_1_0::Host::String(h) => h.clone(),
+        v2_1_0::Host::StringArray(parts) => parts.join("."),
+    });
+    let path = url.path.as_ref().map(|p| match p {
+        v2_1_0::UrlPath::String(p) => p.clone(),
+        v2_1_0::UrlPath::UnionArray(segments) => segments
+            .iter()
+            .map(|s| match s {
+                v2_1_0::PathElement::String(s) => s.as_str(),
+                v2_1_0::PathElement::PathClass(c) => c.value.as_deref().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>()
+            .join("/"),
+    });
+    if host.is_none() && path.is_none() {
+        return None;
+    }
+    let path = match path {
+        Some(p) if !p.is_empty() && !p.starts_with('/') => format!("/{p}"),
+        Some(p) => p,
+        None => String::new(),
+    };
+    // disabled query parameters are not sent, so they are not part of the url
+    let query: Vec<_> = url
+        .query
+        .iter()
+        .flatten()
+        .filter(|q| !q.disabled.unwrap_or(false))
+        .filter_map(|q| match (&q.key, &q.value) {
+            (None, None) => None,
+            (key, None) => Some(key.clone().unwrap_or_default()),
+            (key, Some(value)) => Some(format!("{}={value}", key.as_deref().unwrap_or_default())),
+        })
+        .collect();
+    Some(TemplatedUrl {
+        scheme: url.protocol.clone().filter(|p| !p.is_empty()),
+        host,
+        port: url.port.clone().filter(|p| !p.is_empty()),
+        path,
+        query: (!query.is_empty()).then(|| query.join("&")),
+        fragment: url.hash.clone().filter(|h| !h.is_empty()),
+    })
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Report;
+
+    fn url_class() -> v2_1_0::UrlClass {
+        v2_1_0::UrlClass {
+            protocol: Some("https".to_string()),
+            host: Some(v2_1_0::Host::StringArray(vec![
+                "api".to_string(),
+                "example".to_string(),
+                "com".to_string(),
+            ])),
+            port: Some("8443".to_string()),
+            path: Some(v2_1_0::UrlPath::UnionArray(vec![
+                v2_1_0::PathElement::String("users".to_string()),
+                v2_1_0::PathElement::String(":id".to_string()),
+            ])),
+            query: Some(vec![
+                v2_1_0::QueryParam {
+                    key: Some("expand".to_string()),
+                    value: Some("roles".to_string()),
+                    ..Default::default()
+                },
+                v2_1_0::QueryParam {
+                    key: Some("debug".to_string()),
+                    value: Some("true".to_string()),
+                    disabled: Some(true),
+                    ..Default::default()
+                },
+            ]),
+            hash: Some("top".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn convert(url: v2_1_0::UrlClass, policy: UrlConflictPolicy) -> Result<String, PostresError> {
+        convert_reporting(url, policy, &mut Report::default())
+    }
+
+    fn convert_reporting(
+        url: v2_1_0::UrlClass,
+        policy: UrlConflictPolicy,
+        report: &mut Report,
+    ) -> Result<String, PostresError> {
+        let mut req = super::super::tests::default_postman_request_class();
+        req.url = Some(v2_1_0::Url::UrlClass(url));
+        convert_url(&req, policy, &mut report.for_item(Default::default()))
+    }
+
+    #[test]
+    fn should_convert_path_variables_only_in_path() {
+        assert_eq!(
+            convert_raw_url("{{baseUrl}}:8080/users/:id?at=10:30&next=/:page#/:anchor"),
+            "{{baseUrl}}:8080/users/{{id}}?at=10:30&next=/:page#/:anchor"
+        );
+        assert_eq!(
+            convert_raw_url("http://127.0.0.1:3000/a/:b?c=1"),
+            "http://127.0.0.1:3000/a/{{b}}?c=1"
+        );
+    }
+
+    #[test]
+    fn should_find_disabled_query_params() {
+        let mut req = super::super::tests::default_postman_request_class();
+        req.url = Some(v2_1_0::Url::UrlClass(url_class()));
+        assert_eq!(
+            disabled_query_params(&req),
+            vec![QueryParam::disabled("debug", "true")]
+        );
+        req.url = Some(v2_1_0::Url::String("http://host?debug=true".to_string()));
+        assert!(disabled_query_params(&req).is_empty());
+    }
+
+    #[test]
+    fn should_build_url_from_structured_parts_when_raw_is_missing() {
+        assert_eq!(
+            convert(url_class(), UrlConflictPolicy::Warn).unwrap(),
+            "https://api.example.com:8443/users/{{id}}?expand=roles#top"
+        );
+        let url = v2_1_0::UrlClass {
+            host: Some(v2_1_0::Host::String("{{baseUrl}}".to_string())),
+            path: Some(v2_1_0::UrlPath::String("health".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            convert(url, UrlConflictPolicy::Warn).unwrap(),
+            "{{baseUrl}}/health"
+        );
+    }
+
+    #[test]
+    fn should_use_raw_when_it_matches_structured_parts() {
+        let url = v2_1_0::UrlClass {
+            raw: Some("https://api.example.com:8443/users/:id?expand=roles#top".to_string()),
+            ..url_class()
+        };
+        assert_eq!(
+            convert(url, UrlConflictPolicy::PreferStructured).unwrap(),
+            "https://api.example.com:8443/users/{{id}}?expand=roles#top"
+        );
+    }
+
+    #[test]
+    fn should_use_raw_when_it_differs_from_structured_parts_only_in_writing() {
+        let url = v2_1_0::UrlClass {
+            raw: Some("HTTPS://API.example.com:8443/users/:id?expand=roles&#top".to_string()),
+            ..url_class()
+        };
+        let mut report = Report::default();
+        assert_eq!(
+            convert_reporting(url, UrlConflictPolicy::Warn, &mut report).unwrap(),
+            "HTTPS://API.example.com:8443/users/{{id}}?expand=roles&#top"
+        );
+        let url = v2_1_0::UrlClass {
+            raw: Some("{{baseUrl}}/".to_string()),
+            host: Some(v2_1_0::Host::String("{{baseUrl}}".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_reporting(url, UrlConflictPolicy::Warn, &mut report).unwrap(),
+            "{{baseUrl}}/"
+        );
+        assert_eq!(report.warnings(), 0);
+    }
+
+    #[test]
+    fn should_resolve_conflicts_according_to_policy() {
+        let url = v2_1_0::UrlClass {
+            raw: Some("https://old.example.com/users/:id".to_string()),
+            ..url_class()
+        };
+        assert_eq!(
+            convert(url.clone(), UrlConflictPolicy::PreferRaw).unwrap(),
+            "https://old.example.com/users/{{id}}"
+        );
+        let mut report = Report::default();
+        assert_eq!(
+            convert_reporting(
+                url.clone(),
+                UrlConflictPolicy::PreferStructured,
+                &mut report
+            )
+            .unwrap(),
+            "https://api.example.com:8443/users/{{id}}?expand=roles#top"
+        );
+        assert_eq!(report.warnings(), 0);
+        assert_eq!(
+            convert_reporting(url, UrlConflictPolicy::Warn, &mut report).unwrap(),
+            "https://api.example.com:8443/users/{{id}}?expand=roles#top"
+        );
+        assert_eq!(report.warnings(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "PostmanRequestUrlNotPresent")]
+    fn should_fail_when_url_has_neither_raw_nor_parts() {
+        convert(Default::default(), UrlConflictPolicy::Warn).unwrap();
+    }
 }
@@ -0,0 +1,274 @@
+//! converts postman collection and item variables into RestClient file variables
+
+use std::collections::{HashMap, HashSet};
+
+use fancy_regex::{Captures, Regex};
+use lazy_static::lazy_static;
+
+use crate::{postman::v2_1_0, restclient};
+
+/// all file variables generated for a collection, in the order they should appear in the http file
+#[derive(Debug, Default)]
+pub(crate) struct FileVariables {
+    variables: Vec<restclient::Variable>,
+    used_names: HashSet<String>,
+}
+
+/// the variables visible from a given item
+/// maps the name a variable has in postman to the name of the RestClient file variable holding its value
+#[derive(Clone, Debug, Default)]
+pub(crate) struct VariableScope {
+    names: HashMap<String, String>,
+}
+
+impl FileVariables {
+    /// registers the variables of the collection itself
+    /// these are visible everywhere and keep their original names
+    pub(crate) fn add_collection_variables(
+        &mut self,
+        variables: &Option<Vec<v2_1_0::Variable>>,
+    ) -> VariableScope {
+        self.add_variables("", variables, &VariableScope::default())
+    }
+
+    /// registers variables declared by a folder or request
+    /// RestClient has no concept of scopes, all file variables live in the same namespace. Item variables are therefore prefixed with the item base name, so that two folders declaring the same variable do not overwrite each other.
+    /// Returns the scope visible to the item: its own variables shadow the ones inherited from its parents, exactly like in postman
+    pub(crate) fn add_item_variables(
+        &mut self,
+        basename: &str,
+        variables: &Option<Vec<v2_1_0::Variable>>,
+        parent: &VariableScope,
+    ) -> VariableScope {
+        self.add_variables(basename, variables, parent)
+    }
+
+    /// registers default values of path variables of a request, declared in postman as /users/:id
+    /// path variables belong to a single request, so they are prefixed with the request name. Two requests might use :id with different defaults
+    /// Returns a scope containing only the path variables, because they are only visible in the url path
+    pub(crate) fn add_path_variables(
+        &mut self,
+        request_name: &str,
+        variables: &Option<Vec<v2_1_0::Variable>>,
+    ) -> VariableScope {
+        self.add_variables(request_name, variables, &VariableScope::default())
+    }
+
+    pub(crate) fn into_variables(self) -> Vec<restclient::Variable> {
+        self.variables
+    }
+
+    fn add_variables(
+        &mut self,
+        prefix: &str,
+        variables: &Option<Vec<v2_1_0::Variable>>,
+        parent: &VariableScope,
+    ) -> VariableScope {
+        let mut scope = parent.clone();
+        for variable in variables.iter().flatten() {
+            // per postman specification, key is the variable name. Id is kept for compatibility with older collections
+            let postman_name = match variable.key.as_ref().or(variable.id.as_ref()) {
+                Some(n) if !n.is_empty() => n,
+                _ => continue,
+            };
+            let name = self.unique_name(&if prefix.is_empty() {
+                postman_name.clone()
+            } else {
+                format!("{prefix}_{postman_name}")
+            });
+            let enabled = !matches!(variable.disabled, Some(true));
+            // a disabled variable is not visible to requests, they keep seeing whatever their parents define
+            if enabled {
+                scope.names.insert(postman_name.clone(), name.clone());
+            }
+            self.variables.push(restclient::Variable {
+                description: variable
+                    .description
+                    .as_ref()
+                    .and_then(description_to_string),
+                enabled,
+                name,
+                value: variable_value_to_string(variable),
+            });
+        }
+        scope
+    }
+
+    // finds a name no other file variable has, appending a numeric suffix if needed
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut suffix = 2;
+        while self.used_names.contains(&candidate) {
+            candidate = format!("{name}_{suffix}");
+            suffix += 1;
+        }
+        self.used_names.insert(candidate.clone());
+        candidate
+    }
+}
+
+impl VariableScope {
+    /// rewrites {{name}} references in content so that they point to the file variables visible in this scope
+    /// references to unknown variables (environment variables, for example) are kept untouched
+    pub(crate) fn convert_variables(&self, content: &str) -> String {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r#"\{\{([^{}]+)\}\}"#).unwrap();
+        }
+        RE.replace_all(content, |caps: &Captures| {
+            match self.names.get(caps[1].trim()) {
+                Some(name) => format!("{{{{{name}}}}}"),
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string()
+    }
+}
+
+/// descriptions might be plain text or an object whose content is the text
+fn description_to_string(description: &v2_1_0::DescriptionUnion) -> Option<String> {
+    let text = match description {
+        v2_1_0::DescriptionUnion::String(s) => Some(s),
+        v2_1_0::DescriptionUnion::Description(d) => d.content.as_ref(),
+    };
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// converts the value of a variable to the text RestClient will use
+/// file variables are plain text until the end of the line, so json values are written in their compact form and line breaks are escaped
+fn variable_value_to_string(variable: &v2_1_0::Variable) -> String {
+    let value = match (&variable.value, &variable.variable_type) {
+        (None, _) | (Some(serde_json::Value::Null), _) => String::new(),
+        (Some(serde_json::Value::String(s)), Some(v2_1_0::VariableType::Boolean)) => {
+            // postman stores booleans typed in its ui as strings. Normalize what is clearly a boolean
+            match s.trim().to_lowercase().as_str() {
+                b @ ("true" | "false") => b.to_string(),
+                _ => s.clone(),
+            }
+        }
+        (Some(serde_json::Value::String(s)), Some(v2_1_0::VariableType::Number)) => {
+            match s.trim().parse::<f64>() {
+                Ok(_) => s.trim().to_string(),
+                Err(_) => s.clone(),
+            }
+        }
+        (Some(serde_json::Value::String(s)), _) => s.clone(),
+        (Some(other), _) => other.to_string(),
+    };
+    value.replace("\r\n", "\\n").replace('\n', "\\n")
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn variable(key: &str, value: serde_json::Value) -> v2_1_0::Variable {
+        v2_1_0::Variable {
+            key: Some(key.to_string()),
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_serialize_variable_values_faithfully() {
+        assert_eq!(
+            variable_value_to_string(&variable("a", json!("text"))),
+            "text"
+        );
+        assert_eq!(variable_value_to_string(&variable("a", json!(1.5))), "1.5");
+        assert_eq!(
+            variable_value_to_string(&variable("a", json!(true))),
+            "true"
+        );
+        assert_eq!(variable_value_to_string(&variable("a", json!(null))), "");
+        assert_eq!(
+            variable_value_to_string(&variable("a", json!({"b": [1, 2]}))),
+            r#"{"b":[1,2]}"#
+        );
+        assert_eq!(
+            variable_value_to_string(&variable("a", json!("line1\nline2"))),
+            r#"line1\nline2"#
+        );
+    }
+
+    #[test]
+    fn should_respect_variable_type() {
+        let mut v = variable("a", json!(" TRUE "));
+        v.variable_type = Some(v2_1_0::VariableType::Boolean);
+        assert_eq!(variable_value_to_string(&v), "true");
+        let mut v = variable("a", json!(" 42 "));
+        v.variable_type = Some(v2_1_0::VariableType::Number);
+        assert_eq!(variable_value_to_string(&v), "42");
+        let mut v = variable("a", json!(" 42 "));
+        v.variable_type = Some(v2_1_0::VariableType::String);
+        assert_eq!(variable_value_to_string(&v), " 42 ");
+    }
+
+    #[test]
+    fn should_prefix_item_variables_and_avoid_collisions() {
+        let mut file_variables = FileVariables::default();
+        let root = file_variables.add_collection_variables(&Some(vec![
+            variable("id", json!(1)),
+            variable("a_b", json!(2)),
+        ]));
+        let folder =
+            file_variables.add_item_variables("a", &Some(vec![variable("b", json!(3))]), &root);
+        let other = file_variables.add_item_variables(
+            "Users",
+            &Some(vec![variable("id", json!(4))]),
+            &root,
+        );
+        assert_eq!(
+            file_variables
+                .into_variables()
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "a_b", "a_b_2", "Users_id"]
+        );
+        assert_eq!(
+            folder.convert_variables("/{{a_b}}/{{b}}/{{ id }}"),
+            "/{{a_b}}/{{a_b_2}}/{{id}}"
+        );
+        assert_eq!(
+            other.convert_variables("/{{id}}/{{env}}"),
+            "/{{Users_id}}/{{env}}"
+        );
+    }
+
+    #[test]
+    fn should_keep_disabled_variables_invisible() {
+        let mut file_variables = FileVariables::default();
+        let root = file_variables.add_collection_variables(&Some(vec![variable("id", json!(1))]));
+        let mut disabled = variable("id", json!(2));
+        disabled.disabled = Some(true);
+        let folder = file_variables.add_item_variables("f", &Some(vec![disabled]), &root);
+        assert_eq!(folder.convert_variables("{{id}}"), "{{id}}");
+        let variables = file_variables.into_variables();
+        assert_eq!(variables[1].name, "f_id");
+        assert!(!variables[1].enabled);
+    }
+
+    #[test]
+    fn should_scope_path_variables_to_their_request() {
+        let mut file_variables = FileVariables::default();
+        let mut id = variable("id", json!("42"));
+        id.description = Some(v2_1_0::DescriptionUnion::String("the user id".to_string()));
+        let first = file_variables.add_path_variables("get-user", &Some(vec![id]));
+        let second =
+            file_variables.add_path_variables("get-order", &Some(vec![variable("id", json!("7"))]));
+        assert_eq!(
+            first.convert_variables("/{{id}}/{{baseUrl}}"),
+            "/{{get-user_id}}/{{baseUrl}}"
+        );
+        assert_eq!(second.convert_variables("/{{id}}"), "/{{get-order_id}}");
+        let variables = file_variables.into_variables();
+        assert_eq!(variables[0].description.as_deref(), Some("the user id"));
+        assert_eq!(variables[1].description, None);
+    }
+}
@@ -0,0 +1,293 @@
+//! diagnostics tell where in the source collection something happened and how bad it is
+//! A collection with hundreds of requests is useless to debug with an error which does not say which request failed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use serde::Serialize;
+use strum::Display;
+
+/// how bad a diagnostic is
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// something was converted, but not exactly as postman would send it
+    Warning,
+    /// something could not be converted at all
+    Error,
+}
+
+/// a position in a text file. Both line and column start at 1. Columns are counted in chars, not bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// where a postman item comes from
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ItemOrigin {
+    /// names of the folders containing the item and of the item itself, separated by /. E.g: Users/Admin/Create user
+    pub path: String,
+    /// json pointer to the item in the source file. E.g: /item/0/item/1/item/2
+    pub pointer: String,
+    /// where the item starts in the source file, if known
+    #[serde(flatten)]
+    pub location: Option<Location>,
+}
+
+/// something worth telling about an item
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub origin: ItemOrigin,
+}
+
+impl Diagnostic {
+    pub fn error(origin: ItemOrigin) -> Self {
+        Self {
+            severity: Severity::Error,
+            origin,
+        }
+    }
+
+    pub fn warning(origin: ItemOrigin) -> Self {
+        Self {
+            severity: Severity::Warning,
+            origin,
+        }
+    }
+}
+
+/// written to follow what is being told about the item, e.g: error in Users/Create user at /item/0/item/2 (line 12, column 9)
+impl fmt::Display for ItemOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // errors which are not about any item in particular have no path
+        if !self.path.is_empty() {
+            write!(f, " in {}", self.path)?;
+        }
+        if !self.pointer.is_empty() {
+            write!(f, " at {}", self.pointer)?;
+        }
+        if let Some(location) = &self.location {
+            write!(f, " (line {}, column {})", location.line, location.column)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.severity, self.origin)
+    }
+}
+
+/// finds where the values at the given json pointers start in a json text
+/// serde_json does not keep track of spans, so the text is scanned once more. Pointers not found, or a text which is not valid json, simply give no location
+pub(crate) fn locate(json: &str, pointers: &[&str]) -> HashMap<String, Location> {
+    let mut scanner = Scanner {
+        text: json,
+        pos: 0,
+        location: Location { line: 1, column: 1 },
+        pointer: String::new(),
+        wanted: pointers.iter().copied().collect(),
+        found: HashMap::new(),
+    };
+    scanner.skip_whitespace();
+    // whatever was found before an error is still right
+    let _ = scanner.value();
+    scanner.found
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    location: Location,
+    /// pointer of the value being scanned
+    pointer: String,
+    wanted: HashSet<&'a str>,
+    found: HashMap<String, Location>,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        (self.next()? == expected).then_some(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.next();
+        }
+    }
+
+    fn value(&mut self) -> Option<()> {
+        if self.wanted.contains(self.pointer.as_str()) {
+            self.found.insert(self.pointer.clone(), self.location);
+        }
+        match self.peek()? {
+            '{' => self.object(),
+            '[' => self.array(),
+            '"' => self.string().map(|_| ()),
+            _ => {
+                // numbers, true, false and null end where the enclosing structure continues
+                while !matches!(
+                    self.peek(),
+                    None | Some(',' | '}' | ']' | ' ' | '\t' | '\n' | '\r')
+                ) {
+                    self.next();
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<()> {
+        self.expect('{')?;
+        self.skip_whitespace();
+        if self.peek()? == '}' {
+            return self.expect('}');
+        }
+        loop {
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            self.child(&escape_token(&key))?;
+            self.skip_whitespace();
+            match self.next()? {
+                ',' => self.skip_whitespace(),
+                '}' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<()> {
+        self.expect('[')?;
+        self.skip_whitespace();
+        if self.peek()? == ']' {
+            return self.expect(']');
+        }
+        let mut index = 0;
+        loop {
+            self.child(&index.to_string())?;
+            index += 1;
+            self.skip_whitespace();
+            match self.next()? {
+                ',' => self.skip_whitespace(),
+                ']' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn child(&mut self, token: &str) -> Option<()> {
+        let parent_len = self.pointer.len();
+        self.pointer.push('/');
+        self.pointer.push_str(token);
+        let res = self.value();
+        self.pointer.truncate(parent_len);
+        res
+    }
+
+    /// scans a string, returning it unescaped
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        self.expect('"')?;
+        loop {
+            match self.next()? {
+                '\\' => {
+                    self.next()?;
+                }
+                '"' => break,
+                _ => {}
+            }
+        }
+        serde_json::from_str(&self.text[start..self.pos]).ok()
+    }
+}
+
+/// json pointer tokens escape ~ and /, see RFC 6901
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// the json pointer of a child of the value at the given pointer
+pub(crate) fn child_pointer(parent: &str, token: impl fmt::Display) -> String {
+    format!("{parent}/{}", escape_token(&token.to_string()))
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_locate_values_by_json_pointer() {
+        let json = "{\n  \"item\": [\n    { \"name\": \"a\" },\n    {\"name\": \"b\\\"\", \"a/b\": [1, true]}\n  ]\n}";
+        let found = locate(
+            json,
+            &["", "/item/0", "/item/1", "/item/1/a~1b/1", "/item/2"],
+        );
+        assert_eq!(found[""], Location { line: 1, column: 1 });
+        assert_eq!(found["/item/0"], Location { line: 3, column: 5 });
+        assert_eq!(found["/item/1"], Location { line: 4, column: 5 });
+        assert_eq!(
+            found["/item/1/a~1b/1"],
+            Location {
+                line: 4,
+                column: 32
+            }
+        );
+        assert!(!found.contains_key("/item/2"));
+    }
+
+    #[test]
+    fn should_keep_what_was_found_before_invalid_json() {
+        let found = locate("[{}, {}, oops", &["/0", "/1", "/3"]);
+        assert_eq!(found.len(), 2);
+        assert!(locate("not json", &["/0"]).is_empty());
+    }
+
+    #[test]
+    fn should_tell_where_and_how_bad() {
+        let mut diagnostic = Diagnostic::error(ItemOrigin {
+            path: "Users/Admin/Create user".to_string(),
+            pointer: "/item/0/item/2".to_string(),
+            location: Some(Location {
+                line: 12,
+                column: 9,
+            }),
+        });
+        assert_eq!(
+            diagnostic.to_string(),
+            "error in Users/Admin/Create user at /item/0/item/2 (line 12, column 9)"
+        );
+        diagnostic.severity = Severity::Warning;
+        diagnostic.origin.location = None;
+        diagnostic.origin.pointer = String::new();
+        assert_eq!(diagnostic.to_string(), "warning in Users/Admin/Create user");
+    }
+}
@@ -1,14 +1,27 @@
 use derive_builder::{PostBuildError, UninitializedFieldError};
 use thiserror::Error;
 
+use crate::diagnostics::Diagnostic;
+
 #[derive(Error, Debug)]
 pub enum PostresError {
+    #[error("Destination file {path} already exists. Use --overwrite to replace it")]
+    DestFileAlreadyExistsError { path: String },
+    #[error("Could not write destination file. Caused by {msg}")]
+    DestFileError { msg: String },
+    #[error("Destination file {path} is the source file. Choose another output file")]
+    DestFileIsSourceFileError { path: String },
     #[error("Invalid postman specification: list of items of type {field} is empty")]
     EmptyListOfPostmanItemsError { field: String },
     #[error(transparent)]
     FieldInitializationError(#[from] UninitializedFieldError),
     #[error(transparent)]
     InvalidHttpRequestError(#[from] PostBuildError),
+    #[error("{diagnostic}: {source}")]
+    ItemError {
+        diagnostic: Diagnostic,
+        source: Box<PostresError>,
+    },
     #[error("Invalid postman form data specification. Caused by {msg}")]
     InvalidPostmanFormDataSpecification { msg: String },
     #[error("Invalid postman form file specification. Caused by {msg}")]
@@ -25,6 +38,12 @@ pub enum PostresError {
     LoggingSetupError(#[from] tracing::dispatcher::SetGlobalDefaultError),
     #[error("Postman collection parser error. Caused by {msg}")]
     PostManCollectionParsingError { msg: String },
+    #[error("Could not write postman collection. Caused by {msg}")]
+    PostmanCollectionSerializationError { msg: String },
+    #[error("Postman item could not be parsed. Caused by {msg}")]
+    PostmanItemParsingError { msg: String },
+    #[error("Postman environment {path} parser error. Caused by {msg}")]
+    PostmanEnvironmentParsingError { path: String, msg: String },
     #[error("postman collection version not supported error: {version} not supported.")]
     PostmanCollectionVersionNotSupportedError { version: String },
     #[error("Postman file specification not present on request with body of type file")]
@@ -39,6 +58,8 @@ pub enum PostresError {
     PostmanRequestUrlNotPresent,
     #[error("Postman url encoded specification not present on request with body of type file")]
     PostmanUrlEncodedSpecNotPresent,
+    #[error("Could not update RestClient settings file {path}. Caused by {msg}")]
+    SettingsFileError { path: String, msg: String },
     #[error("Could  not load source file. Caused by {msg}")]
     SourceFileError { msg: String },
 }
@@ -73,6 +94,14 @@ impl PostresError {
         }
     }
 
+    /// tells which item the error happened in
+    pub fn in_item(self, diagnostic: Diagnostic) -> Self {
+        Self::ItemError {
+            diagnostic,
+            source: Box::new(self),
+        }
+    }
+
     pub fn postman_request_not_present(path: impl ToString) -> Self {
         PostresError::PostManRequestNotPresent {
             path: path.to_string(),
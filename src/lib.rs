@@ -51,13 +51,23 @@
 
 mod config;
 mod converter;
+mod diagnostics;
 mod error;
 mod logging;
 mod postman;
+mod report;
 mod restclient;
 mod tree;
 
-pub use config::Config;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub use config::{
+    AuditConfig, Config, ConversionOptions, FakerPolicy, NamingStyle, OverwritePolicy, ParsingMode,
+    UpgradeConfig, UrlConflictPolicy,
+};
 
 /*
     def 006: lib.rs and main.rs files
@@ -69,22 +79,178 @@ pub use config::Config;
     because these are public functions and are in a library, we must document them in a way rust doc understands, so that other people interested in the function can quickly see how they work.
 */
 
+pub use diagnostics::{Diagnostic, ItemOrigin, Location, Severity};
 pub use error::PostresError;
 pub use postman::v2_1_0::PostmanCollection;
+pub use report::{Audit, DroppedFeature, Feature, Report, ReportEntry};
 pub use restclient::HttpRequests;
 
-/// takes a PostmanCollection and returns a HttpCollection, understandable for RestClient
+/// takes a PostmanCollection and returns a HttpCollection, understandable for RestClient, along with a report of what could not be converted as is
 pub fn postman_to_http(
     postman_collection: PostmanCollection,
-) -> Result<HttpRequests, PostresError> {
-    Ok(HttpRequests { requests: vec![] })
+) -> Result<(HttpRequests, Report), PostresError> {
+    postman_to_http_with_options(postman_collection, &ConversionOptions::default())
+}
+
+/// same as postman_to_http, but lets callers decide how some aspects of the conversion are handled
+pub fn postman_to_http_with_options(
+    postman_collection: PostmanCollection,
+    options: &ConversionOptions,
+) -> Result<(HttpRequests, Report), PostresError> {
+    converter::convert_to_http(&postman_collection, options)
 }
 
 /// loads an input file with a PostmanCollection and records on disk the converted file with a http collection, understandable by RestClient
-pub fn postman_file_to_http_file(config: Config) -> Result<(), PostresError> {
-    let postman_collection = postman::load_from_path(&config.source_file)?;
-    let http_requests = postman_to_http(postman_collection)?;
-    Ok(())
+/// if the destination file already exists, config.overwrite decides whether it is replaced or an error is returned
+/// if config.names_file is set, the name given to each request is recorded there, by postman item id
+/// if config.split_by_folder is set, requests are recorded one file per postman folder under that directory instead
+/// files sent as request bodies are referenced relative to the http file using them. Files not found are reported. If config.copy_assets is set, they are copied there
+/// nothing is written if any of the files to be written exists and config.overwrite does not allow replacing it, or if it is the source file
+/// if environment files are informed, they are converted to RestClient environments and merged into the vscode settings file
+/// the returned report tells about items left out or converted differently, it is up to the caller to decide whether that is acceptable
+pub fn postman_file_to_http_file(config: Config) -> Result<Report, PostresError> {
+    let postman_collection = postman::load_from_path(&config.source_file, config.parsing)?;
+    // all sources are loaded before anything is written, so that a bad environment file does not leave a half done conversion behind
+    let environments = config
+        .environment_files
+        .iter()
+        .map(|f| postman::load_environment_from_path(f))
+        .collect::<Result<Vec<_>, _>>()?;
+    // only where requests are written tells whether they are split, whatever the options say
+    let options = ConversionOptions {
+        one_file_per_folder: config.split_by_folder.is_some(),
+        ..config.conversion.clone()
+    };
+    let (mut http_requests, mut report) =
+        converter::convert_to_http(&postman_collection, &options)?;
+    let mut assets = restclient::Assets::new(
+        files_base_dir(&config),
+        config.copy_assets.as_ref().map(PathBuf::from),
+    );
+    for request in http_requests.requests.iter_mut() {
+        let http_dir = match &config.split_by_folder {
+            None => parent_dir(&config.dest_file),
+            Some(dir) => restclient::folder_dir(dir, &request.source().folder),
+        };
+        assets.resolve(request, &http_dir, &mut report);
+    }
+    // names are taken now, because split files take the requests away. They are only written once the http files are, so that they never describe files which do not exist
+    let names = match &config.names_file {
+        Some(names_file) => Some((
+            names_file,
+            converter::names_mapping(&http_requests.requests)?,
+        )),
+        None => None,
+    };
+    let tree = match &config.split_by_folder {
+        Some(dir) => Some((
+            dir,
+            converter::folder_tree(
+                &postman_collection,
+                std::mem::take(&mut http_requests.requests),
+            ),
+        )),
+        None => None,
+    };
+    // every file is checked before the first one is written, for the same reason sources are loaded first
+    let mut outputs = match &tree {
+        Some((dir, tree)) => restclient::folder_files(dir, tree),
+        None => vec![PathBuf::from(&config.dest_file)],
+    };
+    outputs.extend(config.names_file.iter().map(PathBuf::from));
+    outputs.extend(assets.destinations());
+    check_not_source(&outputs, &config.source_file)?;
+    restclient::check_overwrite(&outputs, config.overwrite)?;
+    let vscode_dir = match &tree {
+        None => {
+            restclient::save_to_path(
+                &config.dest_file,
+                &http_requests.to_restclient(),
+                config.overwrite,
+            )?;
+            Path::new(&config.dest_file).with_file_name(".vscode")
+        }
+        Some((dir, tree)) => {
+            restclient::save_folders(dir, tree, &http_requests.variables, config.overwrite)?;
+            Path::new(dir).join(".vscode")
+        }
+    };
+    if let Some((names_file, names)) = names {
+        restclient::save_to_path(names_file, &names, config.overwrite)?;
+    }
+    // assets are copied once the http files referencing them are saved
+    assets.copy(config.overwrite)?;
+    if environments.is_empty() {
+        return Ok(report);
+    }
+    let settings_file = config.settings_file.clone().unwrap_or_else(|| {
+        vscode_dir
+            .join("settings.json")
+            .to_string_lossy()
+            .to_string()
+    });
+    restclient::save_environments(
+        &settings_file,
+        &converter::convert_environments(&environments, &mut report),
+    )?;
+    Ok(report)
+}
+
+/// the collection is only read, never backed up, so writing over it would lose it for good, whatever config.overwrite says
+fn check_not_source(outputs: &[PathBuf], source_file: &str) -> Result<(), PostresError> {
+    // an output which does not exist yet can not be the source, which was just read
+    let source = fs::canonicalize(source_file).ok();
+    match outputs
+        .iter()
+        .find(|o| source.is_some() && fs::canonicalize(o).ok() == source)
+    {
+        Some(output) => Err(PostresError::DestFileIsSourceFileError {
+            path: output.display().to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// files sent as request bodies are looked for in the directory given by the user or, by default, next to the postman file, where they are usually exported with it
+fn files_base_dir(config: &Config) -> PathBuf {
+    match &config.files_base_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => parent_dir(&config.source_file),
+    }
+}
+
+/// the directory of a file, . if the path has no directory part
+fn parent_dir(file: &str) -> PathBuf {
+    match Path::new(file).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// converts a postman file without writing anything, telling how many requests were converted and what was left out
+/// used to estimate how much manual work remains after migrating a collection
+pub fn audit_postman_file(config: AuditConfig) -> Result<Audit, PostresError> {
+    let postman_collection = postman::load_from_path(&config.source_file, config.parsing)?;
+    let (http_requests, report) =
+        postman_to_http_with_options(postman_collection, &config.conversion)?;
+    Ok(Audit {
+        requests: http_requests.requests.len(),
+        report,
+    })
+}
+
+/// loads a postman collection in any supported format and records it on disk as a v 2.1.0 collection
+/// v 2.1.0 collections are also accepted, in which case they are just normalized
+/// collections are always parsed strictly: placeholders of broken items would be written as empty items, silently losing them
+/// the source file is never replaced, even if config.overwrite allows replacing the destination file
+pub fn upgrade_postman_file(config: UpgradeConfig) -> Result<(), PostresError> {
+    let postman_collection = postman::load_from_path(&config.source_file, ParsingMode::Strict)?;
+    check_not_source(&[PathBuf::from(&config.dest_file)], &config.source_file)?;
+    restclient::save_to_path(
+        &config.dest_file,
+        &postman::to_json(&postman_collection)?,
+        config.overwrite,
+    )
 }
 
 /*
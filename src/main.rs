@@ -1,16 +1,24 @@
+use std::process::ExitCode;
+
 use anyhow::Result;
-use clap::Parser;
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
 use fancy_regex::{Captures, Regex};
 use lazy_static::lazy_static;
 use tracing::info;
 use url::Url;
 
-mod error;
 mod logging;
 
-use postres::Config;
+use postres::{
+    AuditConfig, Config, ConversionOptions, FakerPolicy, NamingStyle, OverwritePolicy, ParsingMode,
+    Report, UpgradeConfig, UrlConflictPolicy,
+};
 
 const APP_NAME: &str = "postres";
+/// exit code when the conversion had errors and --fail-on-error or --fail-on-warning was given
+const EXIT_CONVERSION_ERRORS: u8 = 2;
+/// exit code when the conversion had warnings, but no errors, and --fail-on-warning was given
+const EXIT_CONVERSION_WARNINGS: u8 = 3;
 
 /*
     def 001: command line parameters handling
@@ -22,12 +30,164 @@ const APP_NAME: &str = "postres";
     If you are curious to see what a macro expands to, use cargo expand.
 */
 #[derive(Debug, Parser)]
-#[clap(author, version, about)]
+#[clap(author, version, about, args_conflicts_with_subcommands = true)]
 struct Args {
+    /// without a command, the postman file is converted to a http file
+    #[clap(subcommand)]
+    command: Option<Command>,
     #[clap(short, long, value_parser)]
     output_file: Option<String>,
+    /// write one http file per postman folder under this directory, in subdirectories mirroring the folders, instead of a single output file
+    /// RestClient only resolves request variables within a file, so requests chained across folders lose their link
+    #[clap(long, value_parser, conflicts_with = "output_file")]
+    split_by_folder: Option<String>,
     #[clap(short = 'f', long, value_parser, default_value_t = String::from("input.json"))]
     postman_file: String,
+    /// replace the output file if it already exists
+    #[clap(long, overrides_with = "no_clobber")]
+    overwrite: bool,
+    /// refuse to replace an existing output file (default)
+    #[clap(long, overrides_with = "overwrite")]
+    no_clobber: bool,
+    /// postman environment file to be converted to a RestClient environment. Can be repeated
+    #[clap(short, long = "environment", value_parser)]
+    environments: Vec<String>,
+    /// vscode settings file receiving the environments. Defaults to .vscode/settings.json next to the output file
+    #[clap(long, value_parser)]
+    settings_file: Option<String>,
+    /// what to do when the raw url of a request disagrees with its structured parts
+    #[clap(long, value_enum, default_value_t = UrlConflict::Warn)]
+    url_conflict: UrlConflict,
+    /// report items which can not be parsed and convert the rest of the collection, instead of failing
+    #[clap(long)]
+    lenient: bool,
+    /// how request names are built from postman folder and request names
+    #[clap(long, value_enum, default_value_t = Naming::Original)]
+    naming: Naming,
+    /// replace postman faker variables, like {{$randomEmail}}, with fixed sample values instead of leaving them as they are
+    #[clap(long)]
+    faker_samples: bool,
+    /// parse graphql queries and report the ones which are not valid
+    #[clap(long)]
+    validate_graphql: bool,
+    /// keep disabled headers, query parameters and body fields as commented out lines, instead of leaving them out
+    #[clap(long)]
+    keep_disabled: bool,
+    /// write a json file telling the name given to each request, by postman item id
+    #[clap(long, value_parser)]
+    names_file: Option<String>,
+    /// directory where files sent as request bodies are looked for. Defaults to the directory of the postman file
+    #[clap(long, value_parser)]
+    files_base_dir: Option<String>,
+    /// copy every file sent as a request body to this directory, and reference the copies from the http files
+    #[clap(long, value_parser)]
+    copy_assets: Option<String>,
+    /// how the conversion report is printed to stdout. With json, logs go to stderr so that stdout can be parsed
+    #[clap(long, value_enum, default_value_t = ReportFormat::Text)]
+    report_format: ReportFormat,
+    /// exit with code 2 if any item could not be converted
+    #[clap(long)]
+    fail_on_error: bool,
+    /// exit with code 2 if any item could not be converted, or with code 3 if anything was converted with warnings
+    #[clap(long)]
+    fail_on_warning: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// rewrite a v1 or v2.0.0 postman collection as a v2.1.0 collection
+    Upgrade(UpgradeArgs),
+    /// tell which postman features of a collection would be dropped by the conversion, without writing anything
+    Audit(AuditArgs),
+}
+
+#[derive(Debug, ClapArgs)]
+struct AuditArgs {
+    #[clap(short = 'f', long, value_parser, default_value_t = String::from("input.json"))]
+    postman_file: String,
+    /// what to do when the raw url of a request disagrees with its structured parts
+    #[clap(long, value_enum, default_value_t = UrlConflict::Warn)]
+    url_conflict: UrlConflict,
+    /// report items which can not be parsed and audit the rest of the collection, instead of failing
+    #[clap(long)]
+    lenient: bool,
+    /// parse graphql queries and report the ones which are not valid
+    #[clap(long)]
+    validate_graphql: bool,
+    /// how the audit is printed to stdout. With json, logs go to stderr so that stdout can be parsed
+    #[clap(long, value_enum, default_value_t = ReportFormat::Text)]
+    report_format: ReportFormat,
+}
+
+#[derive(Debug, ClapArgs)]
+struct UpgradeArgs {
+    /// defaults to the postman file name ending in .v2.1.0.json
+    #[clap(short, long, value_parser)]
+    output_file: Option<String>,
+    #[clap(short = 'f', long, value_parser, default_value_t = String::from("input.json"))]
+    postman_file: String,
+    /// replace the output file if it already exists
+    #[clap(long, overrides_with = "no_clobber")]
+    overwrite: bool,
+    /// refuse to replace an existing output file (default)
+    #[clap(long, overrides_with = "overwrite")]
+    no_clobber: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// one line per error or warning, followed by a summary
+    Text,
+    /// a json object with the counts of errors and warnings and the list of entries
+    Json,
+}
+
+/// command line counterpart of NamingStyle
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Naming {
+    /// Users_get-user
+    Original,
+    /// users-get-user
+    Kebab,
+    /// users_get_user
+    Snake,
+    /// usersGetUser
+    Camel,
+    /// get-user, without folders
+    Leaf,
+}
+
+impl From<Naming> for NamingStyle {
+    fn from(source: Naming) -> Self {
+        match source {
+            Naming::Original => NamingStyle::Original,
+            Naming::Kebab => NamingStyle::Kebab,
+            Naming::Snake => NamingStyle::Snake,
+            Naming::Camel => NamingStyle::Camel,
+            Naming::Leaf => NamingStyle::Leaf,
+        }
+    }
+}
+
+/// command line counterpart of UrlConflictPolicy, so that the library does not need to know about clap
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum UrlConflict {
+    /// use the raw url
+    Raw,
+    /// use the url built from protocol, host, path and query
+    Structured,
+    /// use the structured url and warn
+    Warn,
+}
+
+impl From<UrlConflict> for UrlConflictPolicy {
+    fn from(source: UrlConflict) -> Self {
+        match source {
+            UrlConflict::Raw => UrlConflictPolicy::PreferRaw,
+            UrlConflict::Structured => UrlConflictPolicy::PreferStructured,
+            UrlConflict::Warn => UrlConflictPolicy::Warn,
+        }
+    }
 }
 
 /*
@@ -60,8 +220,8 @@ struct Args {
 
     These modules are defined in lib.rs. See 008 to have a better explanation on modules.
 */
-fn main() -> Result<()> {
-    let args = Args::parse();
+fn main() -> Result<ExitCode> {
+    let mut args = Args::parse();
     /*
         def 002: printing
         Let's talk a little about printing:
@@ -83,14 +243,53 @@ fn main() -> Result<()> {
         It turns out that this is exactly what the logging subsystem does here.
         You will need to read documentation to better understand how this all works, but at least you have the high level concept of what is going on.
     */
-    let (non_blocking_writer, _guard) = tracing_appender::non_blocking(std::io::stdout());
+    let report_format = match &args.command {
+        Some(Command::Audit(audit)) => audit.report_format,
+        _ => args.report_format,
+    };
+    // a json report must be the only thing on stdout
+    let (non_blocking_writer, _guard) = if report_format == ReportFormat::Json {
+        tracing_appender::non_blocking(std::io::stderr())
+    } else {
+        tracing_appender::non_blocking(std::io::stdout())
+    };
     let subscriber = logging::get_subscriber(APP_NAME, "info", non_blocking_writer);
     logging::init_subscriber(subscriber)?;
 
     info!("program started");
-    postres::postman_file_to_http_file(args.into())?;
+    match args.command.take() {
+        Some(Command::Upgrade(upgrade)) => {
+            postres::upgrade_postman_file(upgrade.into())?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Audit(audit)) => {
+            let audit = postres::audit_postman_file(audit.into())?;
+            match report_format {
+                ReportFormat::Text => print!("{}", audit.to_text()),
+                ReportFormat::Json => println!("{}", audit.to_json()),
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        None => {}
+    }
+    let (fail_on_error, fail_on_warning) = (args.fail_on_error, args.fail_on_warning);
+    let report = postres::postman_file_to_http_file(args.into())?;
+    match report_format {
+        ReportFormat::Text => print!("{}", report.to_text()),
+        ReportFormat::Json => println!("{}", report.to_json()),
+    }
+    Ok(exit_code(&report, fail_on_error, fail_on_warning))
+}
 
-    Ok(())
+/// lets CI gate on the quality of a conversion. Failing on warnings also fails on errors, which are worse
+fn exit_code(report: &Report, fail_on_error: bool, fail_on_warning: bool) -> ExitCode {
+    if (fail_on_error || fail_on_warning) && report.errors() > 0 {
+        ExitCode::from(EXIT_CONVERSION_ERRORS)
+    } else if fail_on_warning && report.warnings() > 0 {
+        ExitCode::from(EXIT_CONVERSION_WARNINGS)
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
 // following trait converts from source to dest
@@ -102,55 +301,149 @@ impl From<Args> for Config {
             this block will assign the result of the matching to the dest_file variable
             To have more information about pattern matching, see 016
             if output file is Some, will will use the value wrapped in the Some variant.
-            if it is None, see comments inside the block
+            if it is None, see comments inside default_dest_file
             The important thing to notice here is that in rust, blocks such as if / else and match return values.
         */
         let dest_file = match source.output_file {
             Some(o) => o,
-            None => {
-                /*
-                    def 004: regex
-                    if output is not provided (it is None), we will replace the file extension from the input file from json to http and use this as an reasonable default
-                    We however have a problem: if the source file contains one or more .json part on its content, we want only the last one to be replaced by .http
-                    this is not an easy task in rust: manipulating strings is not something easy at all, so we will use regex.
-                    It so happens that replacing only the last occurrence of a matched text is also not something easily done in rust
-                    in order to achieve that, we will need to use a regex feature called look ahead.
-                    The idea is simple: we want to capture some text only if we know that no more occurrences of that same text can be matched to the right of the original text
-                    This way, only the last .json is matched.
-                    It so happens that Regex, the di-facto rust Standard crate for regex does not support look ahead.
-                    Because of that, we will use another well maintained crate called, well, fancy_regex.
-                    A regex are created with Regex::new.
-                    The parameter to new is the string representing the regex (not the string the regex is applied in), and, if this string represents a valid regex, it is returned as an instance of the Regex struct.
-                    We call this process of evaluating the string representing the regex and returning an instance, if the regex is valid, regex compyling.
-                */
-                lazy_static! {
-                    /*
-                        def 005: singletons
-                        Why static_ref?
-                        Because compyling a regex is not something neither fast nor simple.
-                        But if you think about it, we just need to compile regexes the first time we use them.
-                        If they contain an error, we panic. If they are correct, we don't need to compile them again and again everytime we want to use them.
-                        Static stuff in rust is not managed in a simple way. Specially complex static objects which might require heap allocations.
-                        The lazy_static declarative macro, in the crate with the same name, provides us with this exact functionality: the expression after the equals sign will be run only the first time this function is called.
-                        From the second time on, the RE static will "remember" its value
-                        This is, therefore, a kind of singleton.
-                        Rust offers other possibilities, and the way you use singletons might be either totally safe or put your code into a trap it will not recover from easily.
-                        In this case, we are creating a singleton which will compile a regex the first time it is used and provide the compiled version for use the next times it is called.
-                        After the first use (when the stat of regex will go from not compiled to compiled), this is never going to change.
-                        This is also not global, the RE variable is only available inside this function.
-                        So, in our opinion, this is both safe and desirable.
-                        Under the hood,  this will envolve a synchromization feature, in order to make sure that if this function is being called by multiple threads only the first call will compile the regex, while all other threads will have to wait and receive the already compiled regex for read only purposes when its compilation ends.
-                        But once it is compiled, because all threads will only read (not change) the data, access is almost instantaneous, way faster than having to compile the regex everytime
-                    */
-                    static ref RE: Regex = Regex::new(r#"(\.json)(?!\.json)"#).unwrap();
-                }
-                RE.replace(&source.postman_file, ".http").to_string()
-            }
+            None => default_dest_file(&source.postman_file, ".http"),
         };
 
         Self {
+            conversion: ConversionOptions {
+                url_conflict: source.url_conflict.into(),
+                naming: source.naming.into(),
+                faker: faker_policy(source.faker_samples),
+                validate_graphql: source.validate_graphql,
+                keep_disabled: source.keep_disabled,
+                one_file_per_folder: source.split_by_folder.is_some(),
+            },
             dest_file,
+            environment_files: source.environments,
+            overwrite: overwrite_policy(source.overwrite),
+            parsing: parsing_mode(source.lenient),
+            settings_file: source.settings_file,
             source_file: source.postman_file,
+            split_by_folder: source.split_by_folder,
+            names_file: source.names_file,
+            files_base_dir: source.files_base_dir,
+            copy_assets: source.copy_assets,
         }
     }
 }
+
+impl From<UpgradeArgs> for UpgradeConfig {
+    fn from(source: UpgradeArgs) -> Self {
+        Self {
+            dest_file: source
+                .output_file
+                .unwrap_or_else(|| default_dest_file(&source.postman_file, ".v2.1.0.json")),
+            overwrite: overwrite_policy(source.overwrite),
+            source_file: source.postman_file,
+        }
+    }
+}
+
+impl From<AuditArgs> for AuditConfig {
+    fn from(source: AuditArgs) -> Self {
+        Self {
+            conversion: ConversionOptions {
+                url_conflict: source.url_conflict.into(),
+                validate_graphql: source.validate_graphql,
+                ..Default::default()
+            },
+            parsing: parsing_mode(source.lenient),
+            source_file: source.postman_file,
+        }
+    }
+}
+
+fn parsing_mode(lenient: bool) -> ParsingMode {
+    if lenient {
+        ParsingMode::Lenient
+    } else {
+        ParsingMode::Strict
+    }
+}
+
+fn faker_policy(faker_samples: bool) -> FakerPolicy {
+    if faker_samples {
+        FakerPolicy::Sample
+    } else {
+        FakerPolicy::Keep
+    }
+}
+
+fn overwrite_policy(overwrite: bool) -> OverwritePolicy {
+    if overwrite {
+        OverwritePolicy::Overwrite
+    } else {
+        OverwritePolicy::NoClobber
+    }
+}
+
+/// the source file name with its .json extension replaced by the given extension, or with the extension appended if it has none
+fn default_dest_file(source_file: &str, extension: &str) -> String {
+    /*
+        def 004: regex
+        if output is not provided (it is None), we will replace the file extension from the input file from json to http and use this as an reasonable default
+        We however have a problem: the source file might contain .json in other places of its name, as in users.json.backup or my.json.files/users.json, and only the extension should be replaced
+        this is not an easy task in rust: manipulating strings is not something easy at all, so we will use regex.
+        The idea is simple: we want to capture .json only if nothing comes after it, which is what the $ anchor means: the end of the text.
+        If the file has no .json extension, nothing matches. Replacing would give the source file name back, and writing to it would destroy the collection, so the extension is appended instead.
+        A regex are created with Regex::new.
+        The parameter to new is the string representing the regex (not the string the regex is applied in), and, if this string represents a valid regex, it is returned as an instance of the Regex struct.
+        We call this process of evaluating the string representing the regex and returning an instance, if the regex is valid, regex compyling.
+    */
+    lazy_static! {
+        /*
+            def 005: singletons
+            Why static_ref?
+            Because compyling a regex is not something neither fast nor simple.
+            But if you think about it, we just need to compile regexes the first time we use them.
+            If they contain an error, we panic. If they are correct, we don't need to compile them again and again everytime we want to use them.
+            Static stuff in rust is not managed in a simple way. Specially complex static objects which might require heap allocations.
+            The lazy_static declarative macro, in the crate with the same name, provides us with this exact functionality: the expression after the equals sign will be run only the first time this function is called.
+            From the second time on, the RE static will "remember" its value
+            This is, therefore, a kind of singleton.
+            Rust offers other possibilities, and the way you use singletons might be either totally safe or put your code into a trap it will not recover from easily.
+            In this case, we are creating a singleton which will compile a regex the first time it is used and provide the compiled version for use the next times it is called.
+            After the first use (when the stat of regex will go from not compiled to compiled), this is never going to change.
+            This is also not global, the RE variable is only available inside this function.
+            So, in our opinion, this is both safe and desirable.
+            Under the hood,  this will envolve a synchromization feature, in order to make sure that if this function is being called by multiple threads only the first call will compile the regex, while all other threads will have to wait and receive the already compiled regex for read only purposes when its compilation ends.
+            But once it is compiled, because all threads will only read (not change) the data, access is almost instantaneous, way faster than having to compile the regex everytime
+        */
+        static ref RE: Regex = Regex::new(r#"\.json$"#).unwrap();
+    }
+    match RE.is_match(source_file) {
+        Ok(true) => RE.replace(source_file, extension).to_string(),
+        _ => format!("{source_file}{extension}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_replace_only_the_json_extension_of_the_source_file() {
+        assert_eq!(default_dest_file("users.json", ".http"), "users.http");
+        assert_eq!(
+            default_dest_file("my.json.files/users.json", ".http"),
+            "my.json.files/users.http"
+        );
+        assert_eq!(
+            default_dest_file("users.json.backup", ".http"),
+            "users.json.backup.http"
+        );
+        assert_eq!(
+            default_dest_file("users.v1.json", ".v2.1.0.json"),
+            "users.v1.v2.1.0.json"
+        );
+        assert_eq!(
+            default_dest_file("users", ".v2.1.0.json"),
+            "users.v2.1.0.json"
+        );
+    }
+}
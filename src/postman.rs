@@ -1,18 +1,207 @@
 //! postman
 //! Emcapsulates the postman_collection operations
 
-use std::fs::File;
+use std::fs::{self, File};
 
-use crate::error::PostresError;
+use serde::{de::DeserializeOwned, Serialize};
 
+use crate::{config::ParsingMode, error::PostresError};
 
+pub(crate) mod environment;
+pub(crate) mod lenient;
+mod origin;
+pub(crate) mod v1;
+pub(crate) mod v2_0_0;
 pub(crate) mod v2_1_0;
 
+pub(crate) use origin::item_name;
+
+use environment::PostmanEnvironment;
 use v2_1_0::PostmanCollection;
 
-pub(crate) fn load_from_path(path: &str) -> Result<PostmanCollection, PostresError> {
-    let src = File::open(path).map_err(|e| PostresError::SourceFileError { msg: e.to_string() })?;
-    let collection: v2_1_0::PostmanCollection = serde_json::from_reader(src)
-        .map_err(|e| PostresError::PostManCollectionParsingError { msg: e.to_string() })?;
+/// collection formats we know how to read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CollectionFormat {
+    V1,
+    V2_0_0,
+    V2_1_0,
+}
+
+/// loads a collection in any supported format, upgrading it to v 2.1.0, the format the rest of the program works with
+/// in lenient mode, items which can not be parsed are replaced by placeholders carrying the error, see the lenient module
+pub(crate) fn load_from_path(
+    path: &str,
+    mode: ParsingMode,
+) -> Result<PostmanCollection, PostresError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| PostresError::SourceFileError { msg: e.to_string() })?;
+    let mut json: serde_json::Value = parse(&content)?;
+    let format = detect_format(&json)?;
+    if mode == ParsingMode::Strict {
+        let mut collection = match format {
+            CollectionFormat::V1 => parse::<v1::PostmanCollection>(&content)?.into(),
+            CollectionFormat::V2_0_0 => parse::<v2_0_0::PostmanCollection>(&content)?.into(),
+            CollectionFormat::V2_1_0 => parse::<v2_1_0::PostmanCollection>(&content)?,
+        };
+        origin::assign_origins(&mut collection, format, &json, &content);
+        return Ok(collection);
+    }
+    let mut placeholders = lenient::Placeholders::default();
+    let mut collection = match format {
+        CollectionFormat::V1 => {
+            placeholders.replace_v1_items(&mut json);
+            from_value::<v1::PostmanCollection>(&json)?.into()
+        }
+        CollectionFormat::V2_0_0 => {
+            placeholders.replace_v2_items::<v2_0_0::Items>(&mut json);
+            from_value::<v2_0_0::PostmanCollection>(&json)?.into()
+        }
+        CollectionFormat::V2_1_0 => {
+            placeholders.replace_v2_items::<v2_1_0::Items>(&mut json);
+            from_value::<v2_1_0::PostmanCollection>(&json)?
+        }
+    };
+    // placeholders keep their positions, so the source json is still right about where they are. Their made up ids are needed to find v1 ones
+    origin::assign_origins(&mut collection, format, &json, &content);
+    placeholders.mark(&mut collection);
     Ok(collection)
 }
+
+// parsing from text, instead of converting the already parsed json value, gives errors with line and column
+fn parse<T: DeserializeOwned>(content: &str) -> Result<T, PostresError> {
+    serde_json::from_str(content)
+        .map_err(|e| PostresError::PostManCollectionParsingError { msg: e.to_string() })
+}
+
+/// writes a collection as v 2.1.0 json
+/// absent fields are left out instead of written as null, which the v 2.1.0 schema rejects in most places.
+/// Object keys are sorted, so that upgrading the same collection twice gives exactly the same file and diffs of upgraded collections only show what really changed
+pub(crate) fn to_json(collection: &PostmanCollection) -> Result<String, PostresError> {
+    let error = |e: serde_json::Error| PostresError::PostmanCollectionSerializationError {
+        msg: e.to_string(),
+    };
+    // without the preserve_order feature, serde_json keeps object keys sorted
+    let mut json = serde_json::to_value(collection).map_err(error)?;
+    remove_nulls(&mut json);
+    // postman exports collections indented with tabs
+    let mut content = vec![];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"\t");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    json.serialize(&mut serializer).map_err(error)?;
+    Ok(String::from_utf8_lossy(&content).to_string())
+}
+
+/// removes object members whose value is null. Nulls inside arrays are kept, as removing them would shift positions
+fn remove_nulls(json: &mut serde_json::Value) {
+    match json {
+        serde_json::Value::Object(members) => {
+            members.retain(|_, v| !v.is_null());
+            members.values_mut().for_each(remove_nulls);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+// what is left after broken items are replaced can still be broken outside items, E.g: in the collection auth
+fn from_value<T: DeserializeOwned>(json: &serde_json::Value) -> Result<T, PostresError> {
+    T::deserialize(json)
+        .map_err(|e| PostresError::PostManCollectionParsingError { msg: e.to_string() })
+}
+
+/// finds out which format a collection is in
+/// v2 collections tell it in info.schema. v1 collections have no info block, but have lists of requests or folders
+pub(crate) fn detect_format(json: &serde_json::Value) -> Result<CollectionFormat, PostresError> {
+    if let Some(info) = json.get("info") {
+        let schema = info.get("schema").and_then(|s| s.as_str()).ok_or_else(|| {
+            PostresError::postman_collection_version_not_supported(
+                "unknown (info.schema is missing)",
+            )
+        })?;
+        // schemas are urls such as https://schema.getpostman.com/json/collection/v2.1.0/collection.json. Their host changed over time, their path did not
+        return match schema.split('/').find(|segment| segment.starts_with('v')) {
+            Some("v2.1.0") => Ok(CollectionFormat::V2_1_0),
+            Some("v2.0.0") => Ok(CollectionFormat::V2_0_0),
+            Some("v1.0.0") => Ok(CollectionFormat::V1),
+            _ => Err(PostresError::postman_collection_version_not_supported(
+                schema,
+            )),
+        };
+    }
+    if ["requests", "folders", "order"]
+        .iter()
+        .any(|field| matches!(json.get(field), Some(f) if f.is_array()))
+    {
+        return Ok(CollectionFormat::V1);
+    }
+    Err(PostresError::postman_collection_version_not_supported(
+        "unknown (no info.schema and not a v1 collection)",
+    ))
+}
+
+pub(crate) fn load_environment_from_path(path: &str) -> Result<PostmanEnvironment, PostresError> {
+    let src = File::open(path).map_err(|e| PostresError::SourceFileError { msg: e.to_string() })?;
+    let environment: PostmanEnvironment =
+        serde_json::from_reader(src).map_err(|e| PostresError::PostmanEnvironmentParsingError {
+            path: path.to_string(),
+            msg: e.to_string(),
+        })?;
+    Ok(environment)
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_detect_collection_format() {
+        assert_eq!(
+            detect_format(&json!({ "info": { "schema": v2_1_0::SCHEMA } })).unwrap(),
+            CollectionFormat::V2_1_0
+        );
+        assert_eq!(
+            detect_format(&json!({ "info": { "schema": "https://schema.postman.com/json/collection/v2.0.0/collection.json" } })).unwrap(),
+            CollectionFormat::V2_0_0
+        );
+        assert_eq!(
+            detect_format(&json!({ "id": "a", "requests": [] })).unwrap(),
+            CollectionFormat::V1
+        );
+    }
+
+    #[test]
+    fn should_report_unknown_formats() {
+        for json in [
+            json!({ "info": { "schema": "https://schema.getpostman.com/json/collection/v3.0.0/collection.json" } }),
+            json!({ "info": { "name": "no schema" } }),
+            json!({ "name": "not a collection" }),
+        ] {
+            assert!(matches!(
+                detect_format(&json),
+                Err(PostresError::PostmanCollectionVersionNotSupportedError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn should_write_collections_without_nulls_and_with_sorted_keys() {
+        let collection: PostmanCollection = serde_json::from_value(json!({
+            "info": { "schema": v2_1_0::SCHEMA, "name": "c" },
+            "item": [{ "name": "r", "request": { "url": "http://host", "method": "GET" } }],
+            "variable": [{ "key": "a", "value": null }]
+        }))
+        .unwrap();
+        let content = to_json(&collection).unwrap();
+        assert!(!content.contains("null"));
+        assert!(content.find("\"info\"").unwrap() < content.find("\"item\"").unwrap());
+        assert!(content.find("\"name\"").unwrap() < content.find("\"schema\"").unwrap());
+        let written: PostmanCollection = serde_json::from_str(&content).unwrap();
+        assert_eq!(written, collection);
+        assert_eq!(to_json(&written).unwrap(), content);
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// A postman environment, as exported by postman.
+/// Environments are not part of the collection, they live in their own files
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct PostmanEnvironment {
+    #[serde(rename = "id")]
+    pub id: Option<String>,
+
+    /// the environment name, E.g: dev, staging, prod
+    #[serde(rename = "name")]
+    pub name: String,
+
+    #[serde(rename = "values")]
+    pub values: Vec<EnvironmentValue>,
+
+    /// always "environment" for environment files. Globals exports use "globals"
+    #[serde(rename = "_postman_variable_scope")]
+    pub postman_variable_scope: Option<String>,
+}
+
+/// A single variable of an environment
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct EnvironmentValue {
+    /// When set to false, the variable is not used by postman. If absent, the variable is assumed to be enabled.
+    #[serde(rename = "enabled")]
+    pub enabled: Option<bool>,
+
+    #[serde(rename = "key")]
+    pub key: String,
+
+    /// either "default" or "secret". Secret values are masked in postman ui
+    #[serde(rename = "type")]
+    pub value_type: Option<String>,
+
+    #[serde(rename = "value")]
+    pub value: Option<serde_json::Value>,
+}
@@ -0,0 +1,202 @@
+//! lenient loading: items which can not be parsed are replaced by placeholders, instead of failing the whole collection
+//! The collection is first read as plain json. Every item is checked on its own against the model of its format, and the broken ones are replaced by minimal items which always parse.
+//! After the collection is parsed and upgraded to v 2.1.0, placeholders are found again by their ids and marked with what went wrong.
+//! Where they are is told by their origin, as for any other item, see the origin module
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+use super::{origin::unnamed_item, v1, v2_1_0};
+
+/// an item replaced by a placeholder, before we know where it ends up in the v 2.1.0 tree
+struct Failure {
+    /// what serde had to say about the item
+    msg: String,
+    /// placeholders of items without a usable id of their own get a made up one, which is removed once they are found
+    made_up_id: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct Placeholders {
+    failures: HashMap<String, Failure>,
+}
+
+impl Placeholders {
+    /// replaces every item of a v2 collection which does not parse as T by a placeholder
+    pub(crate) fn replace_v2_items<T: DeserializeOwned>(&mut self, collection: &mut Value) {
+        if let Some(items) = collection.get_mut("item") {
+            self.replace_tree::<T>(items);
+        }
+    }
+
+    /// replaces every request and folder of a v1 collection which does not parse by a placeholder
+    /// v1 folders and requests are flat lists, placeholders keep their ids so that they are still placed where they belong
+    pub(crate) fn replace_v1_items(&mut self, collection: &mut Value) {
+        for (field, order_fields) in [
+            ("requests", &[][..]),
+            ("folders", &["order", "folders_order"][..]),
+        ] {
+            let entries = match collection.get_mut(field) {
+                Some(Value::Array(entries)) => entries,
+                _ => continue,
+            };
+            for entry in entries.iter_mut() {
+                let res = match field {
+                    "requests" => check::<v1::Request>(entry),
+                    _ => check::<v1::Folder>(entry),
+                };
+                if let Err(msg) = res {
+                    let mut placeholder = self.placeholder(entry, msg, true);
+                    for order_field in order_fields {
+                        if let Some(order) = entry.get(*order_field).filter(|o| is_id_list(o)) {
+                            placeholder.insert(order_field.to_string(), order.clone());
+                        }
+                    }
+                    *entry = Value::Object(placeholder);
+                }
+            }
+        }
+    }
+
+    fn replace_tree<T: DeserializeOwned>(&mut self, items: &mut Value) {
+        let items = match items {
+            Value::Array(items) => items,
+            _ => return,
+        };
+        for item in items.iter_mut() {
+            if let Some(children) = item.get_mut("item") {
+                self.replace_tree::<T>(children);
+            }
+            // children were just checked on their own, so the item is checked without them
+            let mut own = item.clone();
+            if let Some(Value::Array(children)) = own.get_mut("item") {
+                children.clear();
+            }
+            if let Err(msg) = check::<T>(&own) {
+                let mut placeholder = self.placeholder(item, msg, false);
+                if let Some(children) = item.get("item").filter(|c| c.is_array()) {
+                    placeholder.insert("item".to_string(), children.clone());
+                }
+                *item = Value::Object(placeholder);
+            }
+        }
+    }
+
+    /// a placeholder keeps the name of the broken item, which the converter needs
+    /// v1 placeholders also keep their ids, which place them in the tree. v2 ids are not required to be unique, so v2 placeholders always get a made up one
+    fn placeholder(&mut self, item: &Value, msg: String, keep_id: bool) -> Map<String, Value> {
+        let (id, made_up_id) = match item.get("id").and_then(Value::as_str) {
+            Some(id) if keep_id => (id.to_string(), false),
+            _ => (format!("postres-placeholder-{}", self.failures.len()), true),
+        };
+        let mut placeholder = Map::new();
+        placeholder.insert("id".to_string(), json!(id));
+        if let Some(name) = item.get("name").filter(|n| n.is_string()) {
+            placeholder.insert("name".to_string(), name.clone());
+        }
+        self.failures.insert(id, Failure { msg, made_up_id });
+        placeholder
+    }
+
+    /// marks the placeholders found in an upgraded collection with their errors
+    pub(crate) fn mark(mut self, collection: &mut v2_1_0::PostmanCollection) {
+        if self.failures.is_empty() {
+            return;
+        }
+        self.mark_items(&mut collection.item);
+    }
+
+    fn mark_items(&mut self, items: &mut [v2_1_0::Items]) {
+        for (i, item) in items.iter_mut().enumerate() {
+            if let Some(failure) = item.id.as_ref().and_then(|id| self.failures.remove(id)) {
+                if failure.made_up_id {
+                    item.id = None;
+                }
+                // the item usually lost its name along with the rest of it. The placeholder is named after its position, as its origin is, so that both read the same
+                if item.name.is_none() {
+                    item.name = Some(unnamed_item(i));
+                }
+                item.parse_error = Some(failure.msg);
+            }
+            if let Some(children) = &mut item.item {
+                self.mark_items(children);
+            }
+        }
+    }
+}
+
+fn check<T: DeserializeOwned>(item: &Value) -> Result<(), String> {
+    T::deserialize(item).map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn is_id_list(value: &Value) -> bool {
+    matches!(value, Value::Array(ids) if ids.iter().all(Value::is_string))
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item_names(items: &[v2_1_0::Items]) -> Vec<String> {
+        items.iter().map(|i| i.name.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn should_replace_broken_v2_items_keeping_their_children() {
+        let mut json = json!({
+            "info": { "name": "c", "schema": v2_1_0::SCHEMA },
+            "item": [
+                { "name": "ok", "request": "http://host/ok" },
+                { "name": "folder", "auth": "not an auth", "item": [
+                    { "name": "child", "request": "http://host/child" },
+                    { "request": { "url": 42 } }
+                ] }
+            ]
+        });
+        let mut placeholders = Placeholders::default();
+        placeholders.replace_v2_items::<v2_1_0::Items>(&mut json);
+        let mut collection: v2_1_0::PostmanCollection = serde_json::from_value(json).unwrap();
+        placeholders.mark(&mut collection);
+
+        assert_eq!(item_names(&collection.item), vec!["ok", "folder"]);
+        assert_eq!(collection.item[0].parse_error, None);
+        let folder = &collection.item[1];
+        assert!(folder.parse_error.as_ref().unwrap().contains("auth"));
+        assert_eq!(folder.id, None);
+        let children = folder.item.as_ref().unwrap();
+        assert_eq!(item_names(children), vec!["child", "item 2"]);
+        assert_eq!(children[0].parse_error, None);
+        assert!(children[1].parse_error.is_some());
+    }
+
+    #[test]
+    fn should_keep_v1_placeholders_where_they_belong() {
+        let mut json = json!({
+            "id": "c",
+            "name": "c",
+            "folders": [{ "id": "f", "name": "folder", "order": ["r1", "r2"], "auth": 1 }],
+            "requests": [
+                { "id": "r1", "name": "ok", "url": "http://host/ok", "method": "GET" },
+                { "id": "r2", "name": "broken", "url": ["not", "a", "string"] }
+            ]
+        });
+        let mut placeholders = Placeholders::default();
+        placeholders.replace_v1_items(&mut json);
+        let collection: v1::PostmanCollection = serde_json::from_value(json).unwrap();
+        let mut collection: v2_1_0::PostmanCollection = collection.into();
+        placeholders.mark(&mut collection);
+
+        let folder = &collection.item[0];
+        assert!(folder.parse_error.is_some());
+        assert_eq!(folder.id.as_deref(), Some("f"));
+        let children = folder.item.as_ref().unwrap();
+        assert_eq!(item_names(children), vec!["ok", "broken"]);
+        assert_eq!(children[0].parse_error, None);
+        assert!(children[1].parse_error.is_some());
+    }
+}
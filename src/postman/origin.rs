@@ -0,0 +1,180 @@
+//! tells every item of a loaded collection where it was found in the source file, so that diagnostics can point at it
+//! v2 items are found by their position in the item tree, which upgrading to v 2.1.0 does not change.
+//! v1 items are found by their ids in the flat lists of requests and folders.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{v2_1_0, CollectionFormat};
+use crate::diagnostics::{child_pointer, locate, ItemOrigin, Location};
+
+/// name given to an item which has none, by its position among its siblings
+pub(crate) fn unnamed_item(index: usize) -> String {
+    format!("item {}", index + 1)
+}
+
+/// the name of the item or, if it has none, the one given to it by its position among its siblings
+pub(crate) fn item_name(item: &v2_1_0::Items, index: usize) -> String {
+    item.name.clone().unwrap_or_else(|| unnamed_item(index))
+}
+
+/// sets the origin of every item of a collection
+/// json is the parsed source file and content its text, which is needed to find lines and columns
+pub(crate) fn assign_origins(
+    collection: &mut v2_1_0::PostmanCollection,
+    format: CollectionFormat,
+    json: &Value,
+    content: &str,
+) {
+    let v1_pointers = match format {
+        CollectionFormat::V1 => Some(v1_pointers(json)),
+        _ => None,
+    };
+    assign(&mut collection.item, "", "", v1_pointers.as_ref());
+    let mut pointers = vec![];
+    collect_pointers(&collection.item, &mut pointers);
+    let locations = locate(content, &pointers);
+    set_locations(&mut collection.item, &locations);
+}
+
+/// json pointers of v1 requests and folders, by id
+fn v1_pointers(json: &Value) -> HashMap<String, String> {
+    let mut pointers = HashMap::new();
+    for field in ["requests", "folders"] {
+        let entries = json.get(field).and_then(Value::as_array);
+        for (i, entry) in entries.into_iter().flatten().enumerate() {
+            if let Some(id) = entry.get("id").and_then(Value::as_str) {
+                pointers
+                    .entry(id.to_string())
+                    .or_insert_with(|| child_pointer(&child_pointer("", field), i));
+            }
+        }
+    }
+    pointers
+}
+
+fn assign(
+    items: &mut [v2_1_0::Items],
+    parent_path: &str,
+    parent_pointer: &str,
+    v1_pointers: Option<&HashMap<String, String>>,
+) {
+    let items_pointer = child_pointer(parent_pointer, "item");
+    for (i, item) in items.iter_mut().enumerate() {
+        let name = item_name(item, i);
+        let path = if parent_path.is_empty() {
+            name
+        } else {
+            format!("{parent_path}/{name}")
+        };
+        let pointer = match v1_pointers {
+            None => child_pointer(&items_pointer, i),
+            Some(v1_pointers) => item
+                .id
+                .as_ref()
+                .and_then(|id| v1_pointers.get(id))
+                .cloned()
+                .unwrap_or_default(),
+        };
+        if let Some(children) = &mut item.item {
+            assign(children, &path, &pointer, v1_pointers);
+        }
+        item.origin = Some(ItemOrigin {
+            path,
+            pointer,
+            location: None,
+        });
+    }
+}
+
+fn collect_pointers<'a>(items: &'a [v2_1_0::Items], pointers: &mut Vec<&'a str>) {
+    for item in items {
+        if let Some(origin) = &item.origin {
+            pointers.push(&origin.pointer);
+        }
+        if let Some(children) = &item.item {
+            collect_pointers(children, pointers);
+        }
+    }
+}
+
+fn set_locations(items: &mut [v2_1_0::Items], locations: &HashMap<String, Location>) {
+    for item in items {
+        if let Some(origin) = &mut item.origin {
+            origin.location = locations.get(&origin.pointer).copied();
+        }
+        if let Some(children) = &mut item.item {
+            set_locations(children, locations);
+        }
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origins(items: &[v2_1_0::Items]) -> Vec<ItemOrigin> {
+        let mut res = vec![];
+        for item in items {
+            res.push(item.origin.clone().unwrap());
+            res.extend(origins(item.item.as_deref().unwrap_or_default()));
+        }
+        res
+    }
+
+    #[test]
+    fn should_assign_paths_pointers_and_locations_of_v2_items() {
+        let content = r#"{
+  "info": { "name": "c", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+  "item": [
+    { "name": "Users", "item": [
+      { "request": "http://host/users" }
+    ] }
+  ]
+}"#;
+        let json: Value = serde_json::from_str(content).unwrap();
+        let mut collection: v2_1_0::PostmanCollection =
+            serde_json::from_value(json.clone()).unwrap();
+        assign_origins(&mut collection, CollectionFormat::V2_1_0, &json, content);
+        assert_eq!(
+            origins(&collection.item),
+            vec![
+                ItemOrigin {
+                    path: "Users".to_string(),
+                    pointer: "/item/0".to_string(),
+                    location: Some(Location { line: 4, column: 5 }),
+                },
+                ItemOrigin {
+                    path: "Users/item 1".to_string(),
+                    pointer: "/item/0/item/0".to_string(),
+                    location: Some(Location { line: 5, column: 7 }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_point_v1_items_at_their_entries_in_requests_and_folders() {
+        let json = serde_json::json!({
+            "id": "c",
+            "name": "c",
+            "folders": [{ "id": "f", "name": "Users", "order": ["r"] }],
+            "requests": [{ "id": "r", "name": "list", "url": "http://host/users" }]
+        });
+        let content = json.to_string();
+        let collection: super::super::v1::PostmanCollection =
+            serde_json::from_value(json.clone()).unwrap();
+        let mut collection: v2_1_0::PostmanCollection = collection.into();
+        assign_origins(&mut collection, CollectionFormat::V1, &json, &content);
+        let origins = origins(&collection.item);
+        assert_eq!(origins[0].path, "Users");
+        assert_eq!(origins[0].pointer, "/folders/0");
+        assert_eq!(origins[1].path, "Users/list");
+        assert_eq!(origins[1].pointer, "/requests/0");
+        assert!(origins[1].location.is_some());
+    }
+}
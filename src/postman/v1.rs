@@ -0,0 +1,152 @@
+//! legacy postman collection format v1
+//! Unlike v2 formats, v1 collections are not trees: requests and folders are flat lists, and the tree is described by lists of ids (order and folders_order).
+//! There is no info block, which is how v1 collections are told apart from v2 ones.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    v2_0_0::Auth,
+    v2_1_0::{Event, Variable},
+};
+
+mod upgrade;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct PostmanCollection {
+    #[serde(rename = "id")]
+    pub id: Option<String>,
+
+    #[serde(rename = "name")]
+    pub name: Option<String>,
+
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+
+    #[serde(rename = "auth")]
+    pub auth: Option<Auth>,
+
+    #[serde(rename = "events")]
+    pub events: Option<Vec<Event>>,
+
+    #[serde(rename = "variables")]
+    pub variables: Option<Vec<Variable>>,
+
+    /// ids of the requests at the root of the collection, in order
+    #[serde(rename = "order")]
+    pub order: Option<Vec<String>>,
+
+    /// ids of the folders at the root of the collection, in order
+    /// old exports do not have it, in which case every folder not nested in another one is at the root
+    #[serde(rename = "folders_order")]
+    pub folders_order: Option<Vec<String>>,
+
+    #[serde(rename = "folders")]
+    pub folders: Option<Vec<Folder>>,
+
+    #[serde(rename = "requests")]
+    pub requests: Option<Vec<Request>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Folder {
+    #[serde(rename = "id")]
+    pub id: Option<String>,
+
+    #[serde(rename = "name")]
+    pub name: Option<String>,
+
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+
+    #[serde(rename = "auth")]
+    pub auth: Option<Auth>,
+
+    #[serde(rename = "events")]
+    pub events: Option<Vec<Event>>,
+
+    /// ids of the requests in this folder, in order
+    #[serde(rename = "order")]
+    pub order: Option<Vec<String>>,
+
+    /// ids of the folders nested in this folder, in order
+    #[serde(rename = "folders_order")]
+    pub folders_order: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Request {
+    #[serde(rename = "id")]
+    pub id: Option<String>,
+
+    #[serde(rename = "name")]
+    pub name: Option<String>,
+
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+
+    #[serde(rename = "url")]
+    pub url: Option<String>,
+
+    #[serde(rename = "method")]
+    pub method: Option<String>,
+
+    /// headers as text, one name: value per line. Lines starting with // are disabled headers
+    /// only used if headerData is not present
+    #[serde(rename = "headers")]
+    pub headers: Option<String>,
+
+    #[serde(rename = "headerData")]
+    pub header_data: Option<Vec<Parameter>>,
+
+    /// one of raw, urlencoded, params (multipart form), binary or graphql
+    #[serde(rename = "dataMode")]
+    pub data_mode: Option<String>,
+
+    /// form fields, used when data mode is urlencoded or params
+    #[serde(rename = "data")]
+    pub data: Option<Vec<Parameter>>,
+
+    #[serde(rename = "rawModeData")]
+    pub raw_mode_data: Option<String>,
+
+    #[serde(rename = "graphqlModeData")]
+    pub graphql_mode_data: Option<serde_json::Value>,
+
+    #[serde(rename = "pathVariableData")]
+    pub path_variable_data: Option<Vec<Parameter>>,
+
+    #[serde(rename = "auth")]
+    pub auth: Option<Auth>,
+
+    #[serde(rename = "events")]
+    pub events: Option<Vec<Event>>,
+
+    /// older exports keep scripts as plain text instead of events
+    #[serde(rename = "preRequestScript")]
+    pub pre_request_script: Option<String>,
+
+    #[serde(rename = "tests")]
+    pub tests: Option<String>,
+}
+
+/// key / value pairs used for headers, form fields and path variables
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Parameter {
+    #[serde(rename = "key")]
+    pub key: String,
+
+    /// form file fields hold the file path here
+    #[serde(rename = "value")]
+    pub value: Option<serde_json::Value>,
+
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+
+    /// text or file
+    #[serde(rename = "type")]
+    pub parameter_type: Option<String>,
+
+    /// if absent, the parameter is enabled
+    #[serde(rename = "enabled")]
+    pub enabled: Option<bool>,
+}
@@ -0,0 +1,491 @@
+//! maps v1 collections onto the v 2.1.0 model
+//! most of the work is rebuilding the folder tree from the order and folders_order id lists
+
+use std::collections::{HashMap, HashSet};
+
+use super::{Folder, Parameter, PostmanCollection, Request};
+use crate::{postman::v2_1_0, report::Feature};
+
+impl From<PostmanCollection> for v2_1_0::PostmanCollection {
+    fn from(source: PostmanCollection) -> Self {
+        let item = TreeBuilder::new(&source).build();
+        Self {
+            auth: source.auth.map(Into::into),
+            event: source.events,
+            info: v2_1_0::Information {
+                postman_id: source.id,
+                description: source.description.map(v2_1_0::DescriptionUnion::String),
+                name: source.name.unwrap_or_default(),
+                schema: v2_1_0::SCHEMA.to_string(),
+                version: None,
+            },
+            item,
+            variable: source.variables,
+        }
+    }
+}
+
+/// walks the id lists of a v1 collection, turning them into nested items
+/// every folder and request is placed once. Whatever is not referenced by any list ends up at the root, rather than being lost
+struct TreeBuilder<'a> {
+    collection: &'a PostmanCollection,
+    folders: HashMap<&'a str, &'a Folder>,
+    requests: HashMap<&'a str, &'a Request>,
+    placed: HashSet<&'a str>,
+}
+
+impl<'a> TreeBuilder<'a> {
+    fn new(collection: &'a PostmanCollection) -> Self {
+        let folders = collection
+            .folders
+            .iter()
+            .flatten()
+            .filter_map(|f| f.id.as_deref().map(|id| (id, f)))
+            .collect();
+        let requests = collection
+            .requests
+            .iter()
+            .flatten()
+            .filter_map(|r| r.id.as_deref().map(|id| (id, r)))
+            .collect();
+        Self {
+            collection,
+            folders,
+            requests,
+            placed: HashSet::new(),
+        }
+    }
+
+    fn build(mut self) -> Vec<v2_1_0::Items> {
+        let collection = self.collection;
+        let root_folders: Vec<&str> = match &collection.folders_order {
+            Some(order) => order.iter().map(String::as_str).collect(),
+            None => {
+                // without folders_order, the root folders are the ones no other folder claims
+                let nested: HashSet<&str> = collection
+                    .folders
+                    .iter()
+                    .flatten()
+                    .flat_map(|f| f.folders_order.iter().flatten())
+                    .map(String::as_str)
+                    .collect();
+                collection
+                    .folders
+                    .iter()
+                    .flatten()
+                    .filter_map(|f| f.id.as_deref())
+                    .filter(|id| !nested.contains(id))
+                    .collect()
+            }
+        };
+        let mut items = self.items(&root_folders, collection.order.iter().flatten());
+        // anything not placed yet was not referenced anywhere
+        let orphan_folders: Vec<&str> = collection
+            .folders
+            .iter()
+            .flatten()
+            .filter_map(|f| f.id.as_deref())
+            .filter(|id| !self.placed.contains(id))
+            .collect();
+        let orphan_requests: Vec<&String> = collection
+            .requests
+            .iter()
+            .flatten()
+            .filter_map(|r| r.id.as_ref())
+            .filter(|id| !self.placed.contains(id.as_str()))
+            .collect();
+        items.extend(self.items(&orphan_folders, orphan_requests.into_iter()));
+        items
+    }
+
+    // postman lists folders before requests
+    fn items(
+        &mut self,
+        folder_ids: &[&'a str],
+        request_ids: impl Iterator<Item = &'a String>,
+    ) -> Vec<v2_1_0::Items> {
+        let mut items = vec![];
+        for id in folder_ids {
+            if let Some(folder) = self.folders.get(id).copied() {
+                if self.placed.insert(id) {
+                    items.push(self.folder(folder));
+                }
+            }
+        }
+        for id in request_ids {
+            if let Some(request) = self.requests.get(id.as_str()).copied() {
+                if self.placed.insert(id.as_str()) {
+                    items.push(request.into());
+                }
+            }
+        }
+        items
+    }
+
+    fn folder(&mut self, folder: &'a Folder) -> v2_1_0::Items {
+        let nested: Vec<&str> = folder
+            .folders_order
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let item = self.items(&nested, folder.order.iter().flatten());
+        v2_1_0::Items {
+            auth: folder.auth.clone().map(Into::into),
+            description: folder
+                .description
+                .clone()
+                .map(v2_1_0::DescriptionUnion::String),
+            event: folder.events.clone(),
+            id: folder.id.clone(),
+            item: Some(item),
+            name: folder.name.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Request> for v2_1_0::Items {
+    fn from(source: &Request) -> Self {
+        let url = source.url.clone().unwrap_or_default();
+        // path variables can only be declared in structured urls
+        let url = match &source.path_variable_data {
+            Some(variables) if !variables.is_empty() => v2_1_0::Url::UrlClass(v2_1_0::UrlClass {
+                raw: Some(url),
+                variable: Some(
+                    variables
+                        .iter()
+                        .map(|v| v2_1_0::Variable {
+                            key: Some(v.key.clone()),
+                            value: v.value.clone(),
+                            description: v
+                                .description
+                                .clone()
+                                .map(v2_1_0::DescriptionUnion::String),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            _ => v2_1_0::Url::String(url),
+        };
+        let description = source
+            .description
+            .clone()
+            .filter(|d| !d.is_empty())
+            .map(v2_1_0::DescriptionUnion::String);
+        let upgrade_losses = match source.data_mode.as_deref() {
+            Some("binary") => vec![(
+                Feature::BinaryBody,
+                "v1 collections do not record the file sent as binary body".to_string(),
+            )],
+            _ => vec![],
+        };
+        Self {
+            event: events(source),
+            id: source.id.clone(),
+            name: source.name.clone(),
+            request: Some(v2_1_0::RequestUnion::RequestClass(v2_1_0::RequestClass {
+                auth: source.auth.clone().map(Into::into),
+                body: body(source),
+                description: description.clone(),
+                header: headers(source),
+                method: source.method.clone(),
+                url: Some(url),
+                ..Default::default()
+            })),
+            description,
+            upgrade_losses,
+            ..Default::default()
+        }
+    }
+}
+
+fn headers(request: &Request) -> Option<v2_1_0::HeaderUnion> {
+    let headers: Vec<v2_1_0::Header> = match (&request.header_data, &request.headers) {
+        (Some(data), _) => data
+            .iter()
+            .map(|h| v2_1_0::Header {
+                description: h.description.clone().map(v2_1_0::DescriptionUnion::String),
+                disabled: disabled(h),
+                key: h.key.clone(),
+                value: value_to_string(&h.value),
+            })
+            .collect(),
+        (None, Some(text)) => text
+            .lines()
+            .filter_map(|line| {
+                let (line, disabled) = match line.trim().strip_prefix("//") {
+                    Some(l) => (l, Some(true)),
+                    None => (line, None),
+                };
+                let (key, value) = line.split_once(':')?;
+                Some(v2_1_0::Header {
+                    description: None,
+                    disabled,
+                    key: key.trim().to_string(),
+                    value: value.trim().to_string(),
+                })
+            })
+            .collect(),
+        (None, None) => vec![],
+    };
+    if headers.is_empty() {
+        return None;
+    }
+    Some(v2_1_0::HeaderUnion::HeaderArray(headers))
+}
+
+fn body(request: &Request) -> Option<v2_1_0::Body> {
+    let fields = || request.data.iter().flatten();
+    let body = match request.data_mode.as_deref() {
+        Some("raw") => v2_1_0::Body {
+            mode: Some(v2_1_0::Mode::Raw),
+            raw: request.raw_mode_data.clone(),
+            ..Default::default()
+        },
+        Some("urlencoded") => v2_1_0::Body {
+            mode: Some(v2_1_0::Mode::Urlencoded),
+            urlencoded: Some(
+                fields()
+                    .map(|f| v2_1_0::UrlEncodedParameter {
+                        description: f.description.clone().map(v2_1_0::DescriptionUnion::String),
+                        disabled: disabled(f),
+                        key: f.key.clone(),
+                        value: Some(value_to_string(&f.value)),
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+        Some("params") => v2_1_0::Body {
+            mode: Some(v2_1_0::Mode::Formdata),
+            formdata: Some(fields().map(form_parameter).collect()),
+            ..Default::default()
+        },
+        // v1 does not record which file was chosen for binary bodies. A file body without a file could never be converted, so there is no body at all. The item records the loss (see upgrade_losses)
+        Some("binary") => return None,
+        Some("graphql") => v2_1_0::Body {
+            mode: Some(v2_1_0::Mode::Graphql),
+            graphql: request.graphql_mode_data.clone(),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some(body)
+}
+
+fn form_parameter(field: &Parameter) -> v2_1_0::FormParameter {
+    let mut parameter = v2_1_0::FormParameter {
+        description: field
+            .description
+            .clone()
+            .map(v2_1_0::DescriptionUnion::String),
+        disabled: disabled(field),
+        key: field.key.clone(),
+        form_parameter_type: field.parameter_type.clone(),
+        ..Default::default()
+    };
+    if field.parameter_type.as_deref() == Some("file") {
+        // file fields hold the file path, or a list of paths, in their value
+        parameter.src = match &field.value {
+            Some(serde_json::Value::Array(files)) => Some(v2_1_0::FormParameterSrcUnion::Files(
+                files
+                    .iter()
+                    .filter_map(|f| f.as_str())
+                    .map(String::from)
+                    .collect(),
+            )),
+            Some(serde_json::Value::String(file)) => {
+                Some(v2_1_0::FormParameterSrcUnion::File(file.clone()))
+            }
+            _ => None,
+        };
+    } else {
+        parameter.value = Some(value_to_string(&field.value));
+    }
+    parameter
+}
+
+fn events(request: &Request) -> Option<Vec<v2_1_0::Event>> {
+    if request.events.is_some() {
+        return request.events.clone();
+    }
+    let events: Vec<_> = [
+        ("prerequest", &request.pre_request_script),
+        ("test", &request.tests),
+    ]
+    .into_iter()
+    .filter_map(|(listen, script)| {
+        let script = script.as_ref().filter(|s| !s.trim().is_empty())?;
+        Some(v2_1_0::Event {
+            listen: listen.to_string(),
+            script: Some(v2_1_0::Script {
+                exec: Some(v2_1_0::Host::StringArray(
+                    script.lines().map(String::from).collect(),
+                )),
+                script_type: Some("text/javascript".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    })
+    .collect();
+    if events.is_empty() {
+        return None;
+    }
+    Some(events)
+}
+
+fn disabled(parameter: &Parameter) -> Option<bool> {
+    match parameter.enabled {
+        Some(false) => Some(true),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Option<serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn names(items: &[v2_1_0::Items]) -> Vec<String> {
+        items
+            .iter()
+            .map(|i| match &i.item {
+                Some(children) => format!("{}{:?}", i.name.clone().unwrap(), names(children)),
+                None => i.name.clone().unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_rebuild_folder_tree_from_id_lists() {
+        let collection: PostmanCollection = serde_json::from_value(json!({
+            "id": "c",
+            "name": "legacy",
+            "order": ["r1"],
+            "folders_order": ["f1"],
+            "folders": [
+                { "id": "f2", "name": "nested", "order": ["r3"] },
+                { "id": "f1", "name": "outer", "order": ["r2"], "folders_order": ["f2"] }
+            ],
+            "requests": [
+                { "id": "r1", "name": "root", "url": "http://host/1", "method": "GET" },
+                { "id": "r2", "name": "in outer", "url": "http://host/2", "method": "GET" },
+                { "id": "r3", "name": "in nested", "url": "http://host/3", "method": "GET" },
+                { "id": "r4", "name": "orphan", "url": "http://host/4", "method": "GET" }
+            ]
+        }))
+        .unwrap();
+        let upgraded: v2_1_0::PostmanCollection = collection.into();
+        assert_eq!(upgraded.info.name, "legacy");
+        assert_eq!(upgraded.info.schema, v2_1_0::SCHEMA);
+        assert_eq!(
+            names(&upgraded.item),
+            vec![
+                r#"outer["nested[\"in nested\"]", "in outer"]"#,
+                "root",
+                "orphan"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_find_root_folders_without_folders_order() {
+        let collection: PostmanCollection = serde_json::from_value(json!({
+            "folders": [
+                { "id": "f1", "name": "a", "folders_order": ["f3"] },
+                { "id": "f2", "name": "b" },
+                { "id": "f3", "name": "c" }
+            ]
+        }))
+        .unwrap();
+        let upgraded: v2_1_0::PostmanCollection = collection.into();
+        assert_eq!(names(&upgraded.item), vec![r#"a["c[]"]"#, "b[]"]);
+    }
+
+    #[test]
+    fn should_convert_request_fields() {
+        let request: Request = serde_json::from_value(json!({
+            "id": "r",
+            "name": "create",
+            "url": "http://host/users/:id",
+            "method": "POST",
+            "headers": "Content-Type: application/json\n// X-Debug: 1\n",
+            "dataMode": "urlencoded",
+            "data": [
+                { "key": "a", "value": "1", "type": "text" },
+                { "key": "b", "value": "2", "type": "text", "enabled": false }
+            ],
+            "pathVariableData": [{ "key": "id", "value": "1" }],
+            "preRequestScript": "pm.variables.set('a', 1);"
+        }))
+        .unwrap();
+        let item: v2_1_0::Items = (&request).into();
+        let converted = match item.request {
+            Some(v2_1_0::RequestUnion::RequestClass(r)) => r,
+            other => panic!("unexpected request {other:?}"),
+        };
+        assert_eq!(converted.method.as_deref(), Some("POST"));
+        assert_eq!(
+            converted.header,
+            Some(v2_1_0::HeaderUnion::HeaderArray(vec![
+                v2_1_0::Header {
+                    description: None,
+                    disabled: None,
+                    key: "Content-Type".to_string(),
+                    value: "application/json".to_string(),
+                },
+                v2_1_0::Header {
+                    description: None,
+                    disabled: Some(true),
+                    key: "X-Debug".to_string(),
+                    value: "1".to_string(),
+                },
+            ]))
+        );
+        let body = converted.body.unwrap();
+        assert_eq!(body.mode, Some(v2_1_0::Mode::Urlencoded));
+        assert_eq!(body.urlencoded.unwrap()[1].disabled, Some(true));
+        match converted.url {
+            Some(v2_1_0::Url::UrlClass(url)) => {
+                assert_eq!(url.raw.as_deref(), Some("http://host/users/:id"));
+                assert_eq!(url.variable.unwrap()[0].key.as_deref(), Some("id"));
+            }
+            other => panic!("unexpected url {other:?}"),
+        }
+        assert_eq!(item.event.unwrap()[0].listen, "prerequest");
+    }
+
+    #[test]
+    fn should_leave_binary_bodies_out() {
+        let request: Request = serde_json::from_value(json!({
+            "id": "r",
+            "name": "upload",
+            "url": "http://host/upload",
+            "method": "PUT",
+            "dataMode": "binary"
+        }))
+        .unwrap();
+        let item = v2_1_0::Items::from(&request);
+        assert_eq!(item.upgrade_losses[0].0, Feature::BinaryBody);
+        match item.request {
+            Some(v2_1_0::RequestUnion::RequestClass(r)) => assert_eq!(r.body, None),
+            other => panic!("unexpected request {other:?}"),
+        }
+    }
+}
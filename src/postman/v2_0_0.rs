@@ -0,0 +1,189 @@
+//! postman collection format v 2.0.0
+//! It is almost identical to v 2.1.0. The difference is in the auth helpers, which are objects here and lists of key / value attributes there.
+//! Only what differs is declared in this module, everything else is taken from v 2.1.0
+
+use serde::{Deserialize, Serialize};
+
+use super::v2_1_0::{
+    AuthType, Certificate, Cookie, DescriptionUnion, Event, HeaderUnion, Headers, Information,
+    ProtocolProfileBehavior, ProxyConfig, ResponseTime, Url, Variable,
+};
+
+mod upgrade;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct PostmanCollection {
+    #[serde(rename = "auth")]
+    pub auth: Option<Auth>,
+
+    #[serde(rename = "event")]
+    pub event: Option<Vec<Event>>,
+
+    #[serde(rename = "info")]
+    pub info: Information,
+
+    #[serde(rename = "item")]
+    pub item: Vec<Items>,
+
+    #[serde(rename = "variable")]
+    pub variable: Option<Vec<Variable>>,
+}
+
+/// Represents authentication helpers provided by Postman
+/// each helper is an object whose keys are the attribute names, E.g: { "username": "a", "password": "b" }
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Auth {
+    #[serde(rename = "apikey")]
+    pub apikey: Option<AuthAttributes>,
+
+    #[serde(rename = "awsv4")]
+    pub awsv4: Option<AuthAttributes>,
+
+    #[serde(rename = "basic")]
+    pub basic: Option<AuthAttributes>,
+
+    #[serde(rename = "bearer")]
+    pub bearer: Option<AuthAttributes>,
+
+    #[serde(rename = "digest")]
+    pub digest: Option<AuthAttributes>,
+
+    #[serde(rename = "edgegrid")]
+    pub edgegrid: Option<AuthAttributes>,
+
+    #[serde(rename = "hawk")]
+    pub hawk: Option<AuthAttributes>,
+
+    #[serde(rename = "noauth")]
+    pub noauth: Option<serde_json::Value>,
+
+    #[serde(rename = "ntlm")]
+    pub ntlm: Option<AuthAttributes>,
+
+    #[serde(rename = "oauth1")]
+    pub oauth1: Option<AuthAttributes>,
+
+    #[serde(rename = "oauth2")]
+    pub oauth2: Option<AuthAttributes>,
+
+    #[serde(rename = "type")]
+    pub auth_type: AuthType,
+}
+
+pub type AuthAttributes = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Items {
+    #[serde(rename = "description")]
+    pub description: Option<DescriptionUnion>,
+
+    #[serde(rename = "event")]
+    pub event: Option<Vec<Event>>,
+
+    #[serde(rename = "id")]
+    pub id: Option<String>,
+
+    #[serde(rename = "name")]
+    pub name: Option<String>,
+
+    #[serde(rename = "protocolProfileBehavior")]
+    pub protocol_profile_behavior: Option<ProtocolProfileBehavior>,
+
+    #[serde(rename = "request")]
+    pub request: Option<RequestUnion>,
+
+    #[serde(rename = "response")]
+    pub response: Option<Vec<Option<Response>>>,
+
+    #[serde(rename = "variable")]
+    pub variable: Option<Vec<Variable>>,
+
+    #[serde(rename = "auth")]
+    pub auth: Option<Auth>,
+
+    #[serde(rename = "item")]
+    pub item: Option<Vec<Items>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum RequestUnion {
+    RequestClass(RequestClass),
+
+    String(String),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RequestClass {
+    #[serde(rename = "auth")]
+    pub auth: Option<Auth>,
+
+    #[serde(rename = "body")]
+    pub body: Option<super::v2_1_0::Body>,
+
+    #[serde(rename = "certificate")]
+    pub certificate: Option<Certificate>,
+
+    #[serde(rename = "description")]
+    pub description: Option<DescriptionUnion>,
+
+    #[serde(rename = "header")]
+    pub header: Option<HeaderUnion>,
+
+    #[serde(rename = "method")]
+    pub method: Option<String>,
+
+    #[serde(rename = "proxy")]
+    pub proxy: Option<ProxyConfig>,
+
+    #[serde(rename = "url")]
+    pub url: Option<Url>,
+}
+
+/// A response represents an HTTP response.
+/// It has its own declaration because the request which originated it might have auth
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Response {
+    AnythingArray(Vec<Option<serde_json::Value>>),
+
+    Bool(bool),
+
+    Double(f64),
+
+    Integer(i64),
+
+    ResponseClass(ResponseClass),
+
+    String(String),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ResponseClass {
+    #[serde(rename = "body")]
+    pub body: Option<String>,
+
+    #[serde(rename = "code")]
+    pub code: Option<i64>,
+
+    #[serde(rename = "cookie")]
+    pub cookie: Option<Vec<Cookie>>,
+
+    #[serde(rename = "header")]
+    pub header: Option<Headers>,
+
+    #[serde(rename = "id")]
+    pub id: Option<String>,
+
+    #[serde(rename = "originalRequest")]
+    pub original_request: Option<RequestUnion>,
+
+    #[serde(rename = "responseTime")]
+    pub response_time: Option<ResponseTime>,
+
+    #[serde(rename = "timings")]
+    pub timings: Option<serde_json::value::Value>,
+
+    #[serde(rename = "status")]
+    pub status: Option<String>,
+}
@@ -0,0 +1,174 @@
+//! maps v 2.0.0 collections onto the v 2.1.0 model
+//! everything but auth helpers is the same, so most of this is just moving fields around
+
+use super::{Auth, AuthAttributes, Items, PostmanCollection, RequestClass, RequestUnion, Response};
+use crate::postman::v2_1_0;
+
+impl From<PostmanCollection> for v2_1_0::PostmanCollection {
+    fn from(source: PostmanCollection) -> Self {
+        let mut info = source.info;
+        info.schema = v2_1_0::SCHEMA.to_string();
+        Self {
+            auth: source.auth.map(Into::into),
+            event: source.event,
+            info,
+            item: source.item.into_iter().map(Into::into).collect(),
+            variable: source.variable,
+        }
+    }
+}
+
+impl From<Items> for v2_1_0::Items {
+    fn from(source: Items) -> Self {
+        Self {
+            description: source.description,
+            event: source.event,
+            id: source.id,
+            name: source.name,
+            protocol_profile_behavior: source.protocol_profile_behavior,
+            request: source.request.map(Into::into),
+            response: source
+                .response
+                .map(|r| r.into_iter().map(|r| r.map(Into::into)).collect()),
+            variable: source.variable,
+            auth: source.auth.map(Into::into),
+            item: source
+                .item
+                .map(|items| items.into_iter().map(Into::into).collect()),
+            parse_error: None,
+            origin: None,
+            upgrade_losses: vec![],
+        }
+    }
+}
+
+impl From<RequestUnion> for v2_1_0::RequestUnion {
+    fn from(source: RequestUnion) -> Self {
+        match source {
+            RequestUnion::RequestClass(r) => Self::RequestClass(r.into()),
+            RequestUnion::String(r) => Self::String(r),
+        }
+    }
+}
+
+impl From<RequestClass> for v2_1_0::RequestClass {
+    fn from(source: RequestClass) -> Self {
+        Self {
+            auth: source.auth.map(Into::into),
+            body: source.body,
+            certificate: source.certificate,
+            description: source.description,
+            header: source.header,
+            method: source.method,
+            proxy: source.proxy,
+            url: source.url,
+        }
+    }
+}
+
+impl From<Response> for v2_1_0::Response {
+    fn from(source: Response) -> Self {
+        match source {
+            Response::AnythingArray(a) => Self::AnythingArray(a),
+            Response::Bool(b) => Self::Bool(b),
+            Response::Double(d) => Self::Double(d),
+            Response::Integer(i) => Self::Integer(i),
+            Response::ResponseClass(r) => Self::ResponseClass(v2_1_0::ResponseClass {
+                body: r.body,
+                code: r.code,
+                cookie: r.cookie,
+                header: r.header,
+                id: r.id,
+                original_request: r.original_request.map(Into::into),
+                response_time: r.response_time,
+                timings: r.timings,
+                status: r.status,
+            }),
+            Response::String(s) => Self::String(s),
+        }
+    }
+}
+
+impl From<Auth> for v2_1_0::Auth {
+    fn from(source: Auth) -> Self {
+        Self {
+            apikey: source.apikey.map(to_attribute_list),
+            awsv4: source.awsv4.map(to_attribute_list),
+            basic: source.basic.map(to_attribute_list),
+            bearer: source.bearer.map(to_attribute_list),
+            digest: source.digest.map(to_attribute_list),
+            edgegrid: source.edgegrid.map(to_attribute_list),
+            hawk: source.hawk.map(to_attribute_list),
+            noauth: source.noauth,
+            ntlm: source.ntlm.map(to_attribute_list),
+            oauth1: source.oauth1.map(to_attribute_list),
+            oauth2: source.oauth2.map(to_attribute_list),
+            auth_type: source.auth_type,
+        }
+    }
+}
+
+/// { "username": "a" } becomes [{ "key": "username", "value": "a", "type": "string" }]
+pub(crate) fn to_attribute_list(attributes: AuthAttributes) -> Vec<v2_1_0::AuthAttribute> {
+    attributes
+        .into_iter()
+        .map(|(key, value)| {
+            let auth_type = match &value {
+                serde_json::Value::String(_) => "string",
+                serde_json::Value::Bool(_) => "boolean",
+                serde_json::Value::Number(_) => "number",
+                _ => "any",
+            };
+            v2_1_0::AuthAttribute {
+                key,
+                auth_type: Some(auth_type.to_string()),
+                value: Some(value),
+            }
+        })
+        .collect()
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_upgrade_auth_helpers_to_attribute_lists() {
+        let collection: PostmanCollection = serde_json::from_value(json!({
+            "info": {
+                "name": "c",
+                "schema": "https://schema.getpostman.com/json/collection/v2.0.0/collection.json"
+            },
+            "item": [{
+                "name": "r",
+                "request": {
+                    "method": "GET",
+                    "url": "http://host",
+                    "auth": {
+                        "type": "basic",
+                        "basic": { "username": "user", "password": "pass", "showPassword": false }
+                    }
+                }
+            }]
+        }))
+        .unwrap();
+        let upgraded: v2_1_0::PostmanCollection = collection.into();
+        assert_eq!(upgraded.info.schema, v2_1_0::SCHEMA);
+        let request = match &upgraded.item[0].request {
+            Some(v2_1_0::RequestUnion::RequestClass(r)) => r,
+            other => panic!("unexpected request {other:?}"),
+        };
+        let expected: Vec<v2_1_0::AuthAttribute> = serde_json::from_value(json!([
+            { "key": "password", "value": "pass", "type": "string" },
+            { "key": "showPassword", "value": false, "type": "boolean" },
+            { "key": "username", "value": "user", "type": "string" }
+        ]))
+        .unwrap();
+        assert_eq!(request.auth.as_ref().unwrap().basic, Some(expected));
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// schema informed in the info block of v 2.1.0 collections
+pub const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
 /// as per postman specification, the only information provided for the graphql field is that it is a json object
 pub type Graphql = serde_json::Value;
 
@@ -97,7 +100,7 @@ pub struct AuthAttribute {
 /// are stored here, and can be referenced in the collection by their ID.
 ///
 /// Defines a script associated with an associated event name
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct Event {
     /// Indicates whether the event is disabled. If absent, the event is assumed to be enabled.
     #[serde(rename = "disabled")]
@@ -117,7 +120,7 @@ pub struct Event {
 
 /// A script is a snippet of Javascript code that can be used to to perform setup or teardown
 /// operations on a particular response.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct Script {
     #[serde(rename = "exec")]
     pub exec: Option<Host>,
@@ -138,7 +141,7 @@ pub struct Script {
     pub script_type: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct UrlClass {
     /// Contains the URL fragment (if any). Usually this is not transmitted over the network, but
     /// it could be useful to store this in some cases.
@@ -187,7 +190,7 @@ pub struct PathClass {
     pub value: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct QueryParam {
     #[serde(rename = "description")]
     pub description: Option<DescriptionUnion>,
@@ -227,7 +230,7 @@ pub struct Description {
 /// Using variables in your Postman requests eliminates the need to duplicate requests, which
 /// can save a lot of time. Variables can be defined, and referenced to from any part of a
 /// request.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct Variable {
     #[serde(rename = "description")]
     pub description: Option<DescriptionUnion>,
@@ -322,7 +325,7 @@ pub struct CollectionVersionClass {
 /// One of the primary goals of Postman is to organize the development of APIs. To this end,
 /// it is necessary to be able to group requests together. This can be achived using
 /// 'Folders'. A folder just is an ordered set of requests.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct Items {
     #[serde(rename = "description")]
     pub description: Option<DescriptionUnion>,
@@ -361,6 +364,18 @@ pub struct Items {
     /// it. Folders may contain many items.
     #[serde(rename = "item")]
     pub item: Option<Vec<Items>>,
+
+    /// set when lenient loading replaced an item which could not be parsed by a placeholder. Never read from nor written to files
+    #[serde(skip)]
+    pub parse_error: Option<String>,
+
+    /// where the item was found in the source file. Set when collections are loaded from files, never read from nor written to them
+    #[serde(skip)]
+    pub origin: Option<crate::diagnostics::ItemOrigin>,
+
+    /// what upgrading the item from an older collection format left out, and why. Never read from nor written to files
+    #[serde(skip)]
+    pub upgrade_losses: Vec<(crate::report::Feature, String)>,
 }
 
 /// Set of configurations used to alter the usual behavior of sending the request
@@ -371,7 +386,7 @@ pub struct ProtocolProfileBehavior {
     pub disable_body_pruning: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct RequestClass {
     #[serde(rename = "auth")]
     pub auth: Option<Auth>,
@@ -0,0 +1,376 @@
+//! what happened during a conversion: errors, which made items be left out, warnings, about items converted differently from what postman would send,
+//! and postman features which have no RestClient equivalent and were dropped.
+//! The report is handed back to the caller, which decides how to show it and whether the conversion was good enough
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use serde::Serialize;
+use strum::Display;
+
+use crate::{
+    diagnostics::{Diagnostic, ItemOrigin, Severity},
+    error::PostresError,
+};
+
+/// everything worth telling about a conversion, in the order it happened
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub entries: Vec<ReportEntry>,
+    /// features left out of the converted requests. Not warnings: they are known limits of RestClient, not something wrong with the collection
+    pub dropped: Vec<DroppedFeature>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReportEntry {
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+    pub message: String,
+}
+
+/// postman features which have no RestClient equivalent
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Feature {
+    PreRequestScript,
+    TestScript,
+    Certificate,
+    Proxy,
+    ProtocolProfileBehavior,
+    EdgegridAuth,
+    HawkAuth,
+    NtlmAuth,
+    Oauth1Auth,
+    Oauth2Auth,
+    SavedResponses,
+    BinaryBody,
+}
+
+/// a feature an item declares which was left out of the conversion, and why
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DroppedFeature {
+    #[serde(flatten)]
+    pub origin: ItemOrigin,
+    pub feature: Feature,
+    pub reason: String,
+}
+
+/// e.g: dropped pre-request-script in Users/Create user at /item/0/item/2: RestClient does not run scripts
+impl fmt::Display for DroppedFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dropped {}{}: {}",
+            self.feature, self.origin, self.reason
+        )
+    }
+}
+
+impl Report {
+    /// records an error. Errors which know the item they happened in are located at it
+    pub(crate) fn error(&mut self, error: PostresError) {
+        let entry = match error {
+            PostresError::ItemError { diagnostic, source } => ReportEntry {
+                diagnostic,
+                message: source.to_string(),
+            },
+            other => ReportEntry {
+                diagnostic: Diagnostic::error(ItemOrigin::default()),
+                message: other.to_string(),
+            },
+        };
+        self.entries.push(entry);
+    }
+
+    /// the report as seen from a single item, recording everything at the item's origin
+    pub(crate) fn for_item(&mut self, origin: ItemOrigin) -> ItemReport<'_> {
+        ItemReport {
+            origin,
+            report: self,
+        }
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.diagnostic.severity == severity)
+            .count()
+    }
+
+    /// how many times each feature was dropped
+    pub fn dropped_by_feature(&self) -> BTreeMap<Feature, usize> {
+        let mut counts = BTreeMap::new();
+        for d in &self.dropped {
+            *counts.entry(d.feature).or_default() += 1;
+        }
+        counts
+    }
+
+    /// how many distinct items, requests or folders, had something dropped
+    pub fn items_with_dropped_features(&self) -> usize {
+        self.dropped
+            .iter()
+            .map(|d| (&d.origin.path, &d.origin.pointer))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// one line per entry and per dropped feature, followed by a summary
+    pub fn to_text(&self) -> String {
+        let mut text: String = self
+            .entries
+            .iter()
+            .map(|e| format!("{}: {}\n", e.diagnostic, e.message))
+            .collect();
+        for d in &self.dropped {
+            text.push_str(&format!("{d}\n"));
+        }
+        text.push_str(&format!(
+            "conversion finished with {} error(s), {} warning(s) and {} dropped feature(s)\n",
+            self.errors(),
+            self.warnings(),
+            self.dropped.len()
+        ));
+        text
+    }
+
+    /// the report as a json object, with the counts of errors, warnings and dropped features next to them
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct JsonReport<'a> {
+            errors: usize,
+            warnings: usize,
+            dropped: usize,
+            entries: &'a [ReportEntry],
+            dropped_features: &'a [DroppedFeature],
+        }
+        let report = JsonReport {
+            errors: self.errors(),
+            warnings: self.warnings(),
+            dropped: self.dropped.len(),
+            entries: &self.entries,
+            dropped_features: &self.dropped,
+        };
+        // nothing in a report can fail to serialize: there are no maps with non string keys nor failing serialize implementations
+        serde_json::to_string_pretty(&report).unwrap()
+    }
+}
+
+/// records entries located at a single item
+pub(crate) struct ItemReport<'a> {
+    origin: ItemOrigin,
+    report: &'a mut Report,
+}
+
+impl<'a> ItemReport<'a> {
+    pub(crate) fn error(&mut self, error: PostresError) {
+        self.report
+            .error(error.in_item(Diagnostic::error(self.origin.clone())));
+    }
+
+    pub(crate) fn warning(&mut self, message: impl ToString) {
+        self.report.entries.push(ReportEntry {
+            diagnostic: Diagnostic::warning(self.origin.clone()),
+            message: message.to_string(),
+        });
+    }
+
+    pub(crate) fn dropped(&mut self, feature: Feature, reason: impl ToString) {
+        self.report.dropped.push(DroppedFeature {
+            origin: self.origin.clone(),
+            feature,
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// how much of a collection survives the conversion, so that the manual work left after migrating can be estimated
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Audit {
+    /// requests converted
+    pub requests: usize,
+    pub report: Report,
+}
+
+impl Audit {
+    /// the report, followed by how many times each feature was dropped and by the totals
+    pub fn to_text(&self) -> String {
+        let mut text = self.report.to_text();
+        for (feature, count) in self.report.dropped_by_feature() {
+            text.push_str(&format!("{feature}: {count}\n"));
+        }
+        text.push_str(&format!(
+            "{} request(s) converted, {} item(s) with dropped features\n",
+            self.requests,
+            self.report.items_with_dropped_features()
+        ));
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct JsonAudit<'a> {
+            requests: usize,
+            items_with_dropped_features: usize,
+            dropped_by_feature: BTreeMap<String, usize>,
+            report: &'a Report,
+        }
+        let audit = JsonAudit {
+            requests: self.requests,
+            items_with_dropped_features: self.report.items_with_dropped_features(),
+            dropped_by_feature: self
+                .report
+                .dropped_by_feature()
+                .into_iter()
+                .map(|(feature, count)| (feature.to_string(), count))
+                .collect(),
+            report: &self.report,
+        };
+        serde_json::to_string_pretty(&audit).unwrap()
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::diagnostics::Location;
+
+    fn report() -> Report {
+        let mut report = Report::default();
+        let origin = ItemOrigin {
+            path: "Users/create user".to_string(),
+            pointer: "/item/0/item/1".to_string(),
+            location: Some(Location {
+                line: 12,
+                column: 9,
+            }),
+        };
+        report
+            .for_item(origin.clone())
+            .error(PostresError::PostmanRequestUrlNotPresent);
+        report
+            .for_item(origin.clone())
+            .warning("something was lost");
+        report.error(PostresError::PostmanRequestMethodNotPresent);
+        report
+            .for_item(origin)
+            .dropped(Feature::PreRequestScript, "RestClient does not run scripts");
+        report
+    }
+
+    #[test]
+    fn should_count_errors_and_warnings() {
+        let report = report();
+        assert_eq!(report.errors(), 2);
+        assert_eq!(report.warnings(), 1);
+    }
+
+    #[test]
+    fn should_render_report_as_text() {
+        assert_eq!(
+            report().to_text(),
+            "error in Users/create user at /item/0/item/1 (line 12, column 9): postman request url not present\n\
+             warning in Users/create user at /item/0/item/1 (line 12, column 9): something was lost\n\
+             error: postman request method not present\n\
+             dropped pre-request-script in Users/create user at /item/0/item/1 (line 12, column 9): RestClient does not run scripts\n\
+             conversion finished with 2 error(s), 1 warning(s) and 1 dropped feature(s)\n"
+        );
+    }
+
+    #[test]
+    fn should_render_report_as_json() {
+        let json: serde_json::Value = serde_json::from_str(&report().to_json()).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "errors": 2,
+                "warnings": 1,
+                "dropped": 1,
+                "entries": [
+                    {
+                        "severity": "error",
+                        "path": "Users/create user",
+                        "pointer": "/item/0/item/1",
+                        "line": 12,
+                        "column": 9,
+                        "message": "postman request url not present"
+                    },
+                    {
+                        "severity": "warning",
+                        "path": "Users/create user",
+                        "pointer": "/item/0/item/1",
+                        "line": 12,
+                        "column": 9,
+                        "message": "something was lost"
+                    },
+                    {
+                        "severity": "error",
+                        "path": "",
+                        "pointer": "",
+                        "message": "postman request method not present"
+                    }
+                ],
+                "dropped_features": [
+                    {
+                        "path": "Users/create user",
+                        "pointer": "/item/0/item/1",
+                        "line": 12,
+                        "column": 9,
+                        "feature": "pre-request-script",
+                        "reason": "RestClient does not run scripts"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn should_summarize_dropped_features_by_feature_and_item() {
+        let mut report = report();
+        let other = ItemOrigin {
+            path: "Users/delete user".to_string(),
+            pointer: "/item/0/item/2".to_string(),
+            location: None,
+        };
+        report
+            .for_item(other.clone())
+            .dropped(Feature::PreRequestScript, "RestClient does not run scripts");
+        report.for_item(other).dropped(
+            Feature::Proxy,
+            "RestClient uses the proxy configured in vscode",
+        );
+        let audit = Audit {
+            requests: 4,
+            report,
+        };
+        assert_eq!(
+            audit.report.dropped_by_feature(),
+            BTreeMap::from([(Feature::PreRequestScript, 2), (Feature::Proxy, 1)])
+        );
+        assert!(audit.to_text().ends_with(
+            "pre-request-script: 2\nproxy: 1\n4 request(s) converted, 2 item(s) with dropped features\n"
+        ));
+        let json: serde_json::Value = serde_json::from_str(&audit.to_json()).unwrap();
+        assert_eq!(json["items_with_dropped_features"], 2);
+        assert_eq!(
+            json["dropped_by_feature"],
+            json!({ "pre-request-script": 2, "proxy": 1 })
+        );
+    }
+}
@@ -1,13 +1,143 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
 use derive_builder::Builder;
-use strum::Display;
-use url::Url;
+use strum::IntoStaticStr;
+
+use crate::{config::OverwritePolicy, diagnostics::ItemOrigin, error::PostresError};
+
+mod assets;
+mod folders;
+mod multipart;
+mod settings;
+mod templated_url;
 
-use crate::error::PostresError;
+pub(crate) use assets::Assets;
+pub(crate) use folders::{folder_dir, folder_files, save_folders, HttpFolder};
+pub(crate) use settings::save_environments;
+pub use templated_url::TemplatedUrl;
 
 /// represents a http file collection, understandable by RestClient
 pub struct HttpRequests {
     /// list of requests in collection
     pub requests: Vec<HttpRequest>,
+    /// file variables, declared at the top of the file
+    pub variables: Vec<Variable>,
+}
+
+impl HttpRequests {
+    /// converts all variables and requests, in order, to rest client format
+    pub fn to_restclient(&self) -> String {
+        http_file(
+            &self.variables,
+            self.requests.iter().map(|r| r.to_restclient()),
+        )
+    }
+}
+
+/// a http file with the given variables on top of requests already converted to rest client format
+fn http_file(variables: &[Variable], requests: impl Iterator<Item = String>) -> String {
+    let requests = requests.collect::<Vec<_>>().join("\n");
+    if variables.is_empty() {
+        return requests;
+    }
+    let variables = variables
+        .iter()
+        .map(|v| v.to_restclient())
+        .collect::<String>();
+    format!("{variables}\n{requests}")
+}
+
+/// represents a RestClient environment, a named set of variables stored in vscode settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Environment {
+    pub name: String,
+    pub variables: Vec<Variable>,
+}
+
+/// represents a RestClient file variable
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    /// written as a comment right above the variable
+    pub description: Option<String>,
+    /// disabled variables are kept as comments, so that they can easily be turned on again
+    pub enabled: bool,
+    pub name: String,
+    pub value: String,
+}
+
+impl Variable {
+    /// converts a variable to rest client format
+    pub fn to_restclient(&self) -> String {
+        let description: String = self
+            .description
+            .iter()
+            .flat_map(|d| d.lines())
+            .map(|l| format!("# {l}\n"))
+            .collect();
+        let comment = if self.enabled { "" } else { "# " };
+        format!("{description}{comment}@{} = {}\n", self.name, self.value)
+    }
+}
+
+/// fails if any of the files already exists and the policy does not allow replacing it
+/// conversions check every file they write before writing the first one, so that a refused file does not leave a half done conversion behind
+pub(crate) fn check_overwrite(
+    paths: &[PathBuf],
+    policy: OverwritePolicy,
+) -> Result<(), PostresError> {
+    if policy == OverwritePolicy::NoClobber {
+        if let Some(existing) = paths.iter().find(|p| p.exists()) {
+            return Err(PostresError::DestFileAlreadyExistsError {
+                path: existing.to_string_lossy().to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// records the content of a http file at the given path
+/// the file is written atomically: either the whole content is there or the destination is left untouched
+pub(crate) fn save_to_path(
+    path: &str,
+    content: &str,
+    policy: OverwritePolicy,
+) -> Result<(), PostresError> {
+    let dest = Path::new(path);
+    if policy == OverwritePolicy::NoClobber && dest.exists() {
+        return Err(PostresError::DestFileAlreadyExistsError {
+            path: path.to_string(),
+        });
+    }
+    // we never write directly to the destination: should anything fail in the middle (disk full, process killed) a half written http file would be left behind.
+    // Instead, we write a temporary file next to the destination and rename it over the destination, which is atomic when both are on the same file system.
+    let tmp = temp_path_for(dest)?;
+    let res = write_and_sync(&tmp, content).and_then(|_| fs::rename(&tmp, dest));
+    if let Err(e) = res {
+        // the temporary file is garbage now. If removing it also fails there is nothing else we can do, the original error is what matters
+        let _ = fs::remove_file(&tmp);
+        return Err(PostresError::DestFileError { msg: e.to_string() });
+    }
+    Ok(())
+}
+
+fn temp_path_for(dest: &Path) -> Result<PathBuf, PostresError> {
+    let file_name = dest
+        .file_name()
+        .ok_or_else(|| PostresError::DestFileError {
+            msg: format!("{} is not a valid file path", dest.display()),
+        })?
+        .to_string_lossy();
+    Ok(dest.with_file_name(format!(".{file_name}.postres.tmp")))
+}
+
+fn write_and_sync(path: &Path, content: &str) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()
 }
 
 /*
@@ -30,15 +160,14 @@ pub struct HttpRequests {
     build_fn(post_build = "Self::post_build", error = "PostresError")
 )]
 pub struct HttpRequest {
+    /// request body
+    #[builder(default)]
+    body: Body,
     /// headers
     #[builder(default)]
     headers: Vec<Header>,
     /// method
     method: Method,
-    /*
-    /// request body
-    body: Body,
-    */
     /// request name
     name: String,
     /// query parameters
@@ -46,6 +175,54 @@ pub struct HttpRequest {
     query_params: Vec<QueryParam>,
     /// url
     url: String,
+    /// the postman item the request was converted from. Not written to http files
+    #[builder(setter(skip))]
+    source: RequestSource,
+}
+
+/// where a converted request comes from, used to name requests and to split http files by folder
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RequestSource {
+    /// names of the postman folders the request was found in, from the outermost
+    pub folder: Vec<String>,
+    /// postman item id, if the item has one
+    pub id: Option<String>,
+    /// postman item name
+    pub name: String,
+    /// variables the item's test scripts set from its response, in the order they are set
+    pub captures: Vec<ResponseCapture>,
+    /// where the item is in the collection, so that what is found once the request is converted can be reported at it
+    pub origin: ItemOrigin,
+}
+
+/// a variable set from the response of a request. Requests sent after it can reference the value through a request variable
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ResponseCapture {
+    pub variable: String,
+    pub value: ResponseValue,
+}
+
+/// the part of a response RestClient request variables can reference
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ResponseValue {
+    /// the whole body, as text
+    Body,
+    /// a header, by name
+    Header(String),
+    /// a value inside a json body, as a JSONPath like $.data.token
+    JsonPath(String),
+}
+
+impl ResponseValue {
+    /// the RestClient reference to this value in the response of the named request, as in {{login.response.body.$.token}}
+    pub(crate) fn reference(&self, request_name: &str) -> String {
+        let path = match self {
+            ResponseValue::Body => "body.*".to_string(),
+            ResponseValue::Header(name) => format!("headers.{name}"),
+            ResponseValue::JsonPath(path) => format!("body.{path}"),
+        };
+        format!("{{{{{request_name}.response.{path}}}}}")
+    }
 }
 
 impl HttpRequestBuilder {
@@ -62,50 +239,160 @@ impl HttpRequestBuilder {
             Urls can come with query strings appended (http://host/path?querystring)
             We want to extract the query string parameters and let the url without them as the request url, mainly because RestClient allows for a nicer way to represent query parameters
             This means that we might need to change the url field. If it contains query strings, we will need to remove them and store them im the query_params field.
-            We will use the url crate to extract the query params (if they exist) and fill the vecor of query parameters of this request appropriately
+            We use TemplatedUrl to extract the query params (if they exist) and fill the vecor of query parameters of this request appropriately
+            The url crate can not be used here, because urls frequently start with a variable, as in {{baseUrl}}/users, and such urls are not valid urls until the variable is replaced.
         */
-
-        // try to find the first "?" on url
-
-        if let Some(p) = instance.url.find("?") {
-            // there is a "?", because the pattern matched Some(p). Parsing the url
-            let url = Url::parse(&instance.url).unwrap();
-            // with a copy of the url parsed, truncate the original url to cut the query string part.
-            // the p wrapped in Some contains the position of the found "?"
-            // we can change the url field because we have a &mut reference.
-            instance.url.truncate(p);
-
+        let mut url = TemplatedUrl::parse(&instance.url);
+        if url.query.is_some() {
             // add the query params extracted from the url to the request query params
-            for (k, v) in url.query_pairs() {
+            for (k, v) in url.query_params() {
                 instance.query_params.push(QueryParam::new(k, v));
             }
+            url.query = None;
+            // RestClient appends query lines to the url, so a fragment would end up before the query string. Fragments are not sent to servers anyway
+            url.fragment = None;
+            // we can change the url field because we have a &mut reference.
+            instance.url = url.to_string();
         }
         Ok(())
     }
 }
 
 impl HttpRequest {
+    /// applies a text transformation to every part of the request which can reference variables: url, query parameters, headers and body
+    pub(crate) fn rewrite_text(&mut self, f: impl Fn(&str) -> String) {
+        self.rewrite_url(&f);
+        for param in self.query_params.iter_mut() {
+            param.name = f(&param.name);
+            param.value = f(&param.value);
+        }
+        for header in self.headers.iter_mut() {
+            header.name = f(&header.name);
+            header.value = f(&header.value);
+        }
+        self.body.rewrite_text(&f);
+    }
+
+    /// applies a text transformation to the url only, query parameters excluded
+    pub(crate) fn rewrite_url(&mut self, f: impl Fn(&str) -> String) {
+        self.url = f(&self.url);
+    }
+
+    /// applies a transformation to the paths of the files the body is read from, form files included
+    pub(crate) fn rewrite_files(&mut self, mut f: impl FnMut(&str) -> String) {
+        match &mut self.body {
+            Body::FileSource(path) => *path = f(path),
+            Body::FormData(params) => {
+                for param in params.iter_mut() {
+                    if let FormParamValue::File(files) = &mut param.value {
+                        files.iter_mut().for_each(|file| *file = f(file));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// adds a header, unless the request already has one with the same name. Disabled headers are not sent, so they do not count
+    /// returns false if the header was not added
+    pub(crate) fn add_header_if_absent(&mut self, header: Header) -> bool {
+        if self
+            .headers
+            .iter()
+            .any(|h| h.enabled && h.name.eq_ignore_ascii_case(&header.name))
+        {
+            return false;
+        }
+        self.headers.push(header);
+        true
+    }
+
+    pub(crate) fn set_source(&mut self, source: RequestSource) {
+        self.source = source;
+    }
+
+    pub(crate) fn source(&self) -> &RequestSource {
+        &self.source
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// appends a query parameter after the ones already present
+    pub(crate) fn add_query_param(&mut self, param: QueryParam) {
+        self.query_params.push(param);
+    }
+
     /// converts a request to rest client format
     pub fn to_restclient(&self) -> String {
         let mut builder = string_builder::Builder::default();
         builder.append(format!("#####\n# @name {}\n\n", self.name));
-        builder.append(format!(
-            "{} {} http/1.1\n",
-            &self.method.to_string(),
-            &self.url
-        ));
+        builder.append(format!("{} {}", &self.method.to_string(), &self.url));
+        // RestClient allows query parameters to be spread one per line, as long as lines start with either ? (the first one) or & (the others)
+        // disabled parameters are commented out. They start with &, unless no parameter is enabled, so that removing the # is enough to send them
+        let any_enabled = self.query_params.iter().any(|p| p.enabled);
+        let mut first = true;
+        for param in &self.query_params {
+            let param_line = format!("{}={}", param.name, param.value);
+            if !param.enabled {
+                let separator = if any_enabled { '&' } else { '?' };
+                builder.append(format!("\n    # {separator}{param_line}"));
+                continue;
+            }
+            let separator = if first { '?' } else { '&' };
+            first = false;
+            builder.append(format!("\n    {separator}{param_line}"));
+        }
+        // the version ends the request line, so it can not follow a comment. RestClient assumes http/1.1 when it is missing
+        match self.query_params.last() {
+            Some(param) if !param.enabled => builder.append("\n"),
+            _ => builder.append(" http/1.1\n"),
+        }
+        let body_headers = self.body.headers();
+        for header in self
+            .headers
+            .iter()
+            // the body might need to impose its own version of a header, as it happens with the multipart boundary in the content type
+            .filter(|h| {
+                !body_headers
+                    .iter()
+                    .any(|b| b.name.eq_ignore_ascii_case(&h.name))
+            })
+            .chain(body_headers.iter())
+        {
+            let comment = if header.enabled { "" } else { "# " };
+            builder.append(format!("{comment}{}: {}\n", header.name, header.value));
+        }
+        // bodies are sent exactly as written, comments included. Disabled fields are listed with the headers instead, where RestClient skips comments
+        for field in self.body.disabled_fields() {
+            builder.append(format!("# {field}\n"));
+        }
+        if let Some(body) = self.body.to_restclient() {
+            // in http, a blank line separates headers from the body
+            builder.append("\n");
+            builder.append(body);
+        }
         builder.string().unwrap()
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Header {
+    /// disabled headers are kept as comments, so that they can easily be turned on again
+    pub enabled: bool,
     pub name: String,
     pub value: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct QueryParam {
+    /// disabled parameters are kept as comments, so that they can easily be turned on again
+    enabled: bool,
     name: String,
     value: String,
 }
@@ -113,10 +400,19 @@ pub struct QueryParam {
 impl QueryParam {
     pub fn new(name: impl ToString, value: impl ToString) -> Self {
         Self {
+            enabled: true,
             name: name.to_string(),
             value: value.to_string(),
         }
     }
+
+    /// a parameter postman has, but does not send
+    pub fn disabled(name: impl ToString, value: impl ToString) -> Self {
+        Self {
+            enabled: false,
+            ..Self::new(name, value)
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -135,7 +431,123 @@ impl Default for Body {
     }
 }
 
-#[derive(Clone, Debug, Display, PartialEq, Eq)]
+impl Body {
+    fn rewrite_text(&mut self, f: &impl Fn(&str) -> String) {
+        match self {
+            Body::Empty => {}
+            Body::FileSource(path) => *path = f(path),
+            Body::FormData(params) => {
+                for param in params.iter_mut() {
+                    param.name = f(&param.name);
+                    match &mut param.value {
+                        FormParamValue::File(files) => {
+                            files.iter_mut().for_each(|file| *file = f(file))
+                        }
+                        FormParamValue::Text(text) => *text = f(text),
+                    }
+                }
+            }
+            Body::Graphql(spec) => {
+                spec.spec = f(&spec.spec);
+                spec.variables = spec.variables.as_ref().map(|v| f(v));
+            }
+            Body::Raw(raw) => *raw = f(raw),
+            Body::UrlEncoded(params) => {
+                for param in params.iter_mut() {
+                    param.name = f(&param.name);
+                    param.value = f(&param.value);
+                }
+            }
+        }
+    }
+
+    /// headers RestClient requires to be sent together with this body
+    fn headers(&self) -> Vec<Header> {
+        match self {
+            Body::FormData(params) => vec![Header {
+                enabled: true,
+                name: "Content-Type".to_string(),
+                value: format!(
+                    "multipart/form-data; boundary={}",
+                    multipart::boundary(params)
+                ),
+            }],
+            Body::Graphql(spec) if spec.operation_name.is_none() => vec![Header {
+                enabled: true,
+                name: "X-REQUEST-TYPE".to_string(),
+                value: "GraphQL".to_string(),
+            }],
+            _ => vec![],
+        }
+    }
+
+    /// fields postman has but does not send, as they would be written if they were enabled
+    fn disabled_fields(&self) -> Vec<String> {
+        match self {
+            Body::FormData(params) => params
+                .iter()
+                .filter(|p| !p.enabled)
+                .flat_map(|p| match &p.value {
+                    FormParamValue::Text(text) => vec![format!("{}={text}", p.name)],
+                    FormParamValue::File(files) => files
+                        .iter()
+                        .map(|f| format!("{}=< {}", p.name, file_reference(f)))
+                        .collect(),
+                })
+                .collect(),
+            Body::UrlEncoded(params) => params
+                .iter()
+                .filter(|p| !p.enabled)
+                .map(|p| format!("&{}={}", p.name, p.value))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// converts a body to rest client format
+    /// returns None if there is nothing to be sent
+    fn to_restclient(&self) -> Option<String> {
+        let body = match self {
+            Body::Empty => return None,
+            Body::FileSource(path) => format!("< {}", file_reference(path)),
+            Body::FormData(params) => {
+                multipart::to_restclient(params, &multipart::boundary(params))
+            }
+            Body::Graphql(spec) if spec.operation_name.is_some() => spec.to_json(),
+            Body::Graphql(spec) => match &spec.variables {
+                // RestClient expects the variables, if any, as a json block separated from the query by a blank line
+                Some(variables) => format!("{}\n\n{}", spec.spec.trim_end(), variables),
+                None => spec.spec.clone(),
+            },
+            Body::Raw(raw) => raw.clone(),
+            Body::UrlEncoded(params) => params
+                .iter()
+                .filter(|p| p.enabled)
+                .map(|p| format!("{}={}", p.name, p.value))
+                .collect::<Vec<_>>()
+                .join("\n&"),
+        };
+        // we always end a request with a line break, so that the next request separator starts at its own line
+        Some(format!("{}\n", body.trim_end_matches('\n')))
+    }
+}
+
+/// RestClient reads bodies from files when the body is a line starting with <
+/// paths not starting with / or . are made explicitly relative, so that readers know where to look for them. Windows absolute paths, as in C:\files, are kept
+fn file_reference(path: &str) -> String {
+    if path.starts_with('/') || path.starts_with('.') || has_drive(path) {
+        path.to_string()
+    } else {
+        format!("./{path}")
+    }
+}
+
+/// whether the path is a windows absolute path, as in C:\files, whatever the system we run on
+fn has_drive(path: &str) -> bool {
+    matches!(path.as_bytes(), [drive, b':', b'\\' | b'/', ..] if drive.is_ascii_alphabetic())
+}
+
+#[derive(Clone, Debug, IntoStaticStr, PartialEq, Eq)]
 #[strum(serialize_all = "UPPERCASE")]
 pub enum Method {
     Copy,
@@ -153,17 +565,70 @@ pub enum Method {
     Unlink,
     Unlock,
     View,
+    /// any other method, such as SEARCH, REPORT or MKCOL, written exactly as it was spelled
+    /// use Method::extension to build it, so that the name is known to be valid
+    Extension(String),
+}
+
+impl Method {
+    /// creates an extension method, or None if the name is not a valid http method name
+    /// per RFC 9110, a method name is a token: one or more visible characters other than delimiters
+    pub fn extension(name: &str) -> Option<Self> {
+        let is_tchar = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+        if name.is_empty() || !name.chars().all(is_tchar) {
+            return None;
+        }
+        Some(Self::Extension(name.to_string()))
+    }
+}
+
+// strum would print the variant name for Extension, not the method it holds
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Extension(name) => f.write_str(name),
+            known => f.write_str(known.into()),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GraphqlSpec {
     pub spec: String,
+    /// json object, already laid out
     pub variables: Option<String>,
+    /// which of the operations in spec is run, when it has several
+    pub operation_name: Option<String>,
+}
+
+impl GraphqlSpec {
+    /// the json document graphql servers receive. RestClient has no way to send an operation name with its graphql requests, so they are sent as json ones instead
+    fn to_json(&self) -> String {
+        let mut fields = vec![format!(
+            "    \"query\": {}",
+            serde_json::Value::String(self.spec.clone())
+        )];
+        if let Some(operation_name) = &self.operation_name {
+            fields.push(format!(
+                "    \"operationName\": {}",
+                serde_json::Value::String(operation_name.clone())
+            ));
+        }
+        if let Some(variables) = &self.variables {
+            fields.push(format!(
+                "    \"variables\": {}",
+                variables.trim().replace('\n', "\n    ")
+            ));
+        }
+        format!("{{\n{}\n}}", fields.join(",\n"))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FormDataParamSpec {
     pub content_type: Option<String>,
+    /// disabled fields are not part of the body. They are listed as comments above it, so that they can easily be turned on again
+    pub enabled: bool,
     pub name: String,
     pub value: FormParamValue,
 }
@@ -199,4 +664,336 @@ mod tests {
 
         assert_eq!(req.to_restclient(), result);
     }
+
+    #[test]
+    fn should_extract_query_params_from_templated_url() {
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Get)
+            .url("{{baseUrl}}/x?y=1&name=a%20b#top")
+            .build()
+            .unwrap();
+        assert_eq!(req.url, "{{baseUrl}}/x");
+        assert_eq!(
+            req.query_params,
+            vec![QueryParam::new("y", "1"), QueryParam::new("name", "a%20b")]
+        );
+    }
+
+    #[test]
+    fn variable_description_is_written_as_comment() {
+        let variable = Variable {
+            description: Some("user id\nmust exist".to_string()),
+            enabled: true,
+            name: "id".to_string(),
+            value: "1".to_string(),
+        };
+        assert_eq!(
+            variable.to_restclient(),
+            "# user id\n# must exist\n@id = 1\n"
+        );
+    }
+
+    #[test]
+    fn http_file_is_generated_with_variables_before_requests() {
+        let result = indoc! {r#"
+            @baseUrl = http://127.0.0.1:3000
+            # @token = abc
+
+            #####
+            # @name testReq
+
+            GET {{baseUrl}}/a http/1.1
+        "#};
+        let requests = HttpRequests {
+            requests: vec![HttpRequestBuilder::default()
+                .name("testReq")
+                .method(Method::Get)
+                .url("{{baseUrl}}/a")
+                .build()
+                .unwrap()],
+            variables: vec![
+                Variable {
+                    description: None,
+                    enabled: true,
+                    name: "baseUrl".to_string(),
+                    value: "http://127.0.0.1:3000".to_string(),
+                },
+                Variable {
+                    description: None,
+                    enabled: false,
+                    name: "token".to_string(),
+                    value: "abc".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(requests.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_is_generated_with_query_params_and_headers() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            GET http://127.0.0.1:3000/a/b
+                ?aaa=111
+                &bbb=222 http/1.1
+            Accept: application/json
+            X-Trace: 1
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Get)
+            .url("http://127.0.0.1:3000/a/b?aaa=111&bbb=222")
+            .headers(vec![
+                Header {
+                    enabled: true,
+                    name: "Accept".to_string(),
+                    value: "application/json".to_string(),
+                },
+                Header {
+                    enabled: true,
+                    name: "X-Trace".to_string(),
+                    value: "1".to_string(),
+                },
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_is_generated_with_raw_body() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/a http/1.1
+            Content-Type: application/json
+
+            {"a": 1}
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/a")
+            .headers(vec![Header {
+                enabled: true,
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            }])
+            .body(Body::Raw("{\"a\": 1}".to_string()))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_is_generated_with_file_body() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/a http/1.1
+
+            < ./data/payload.json
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/a")
+            .body(Body::FileSource("data/payload.json".to_string()))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_is_generated_with_url_encoded_body() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/a http/1.1
+
+            a=1
+            &b=2
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/a")
+            .body(Body::UrlEncoded(vec![
+                QueryParam::new("a", "1"),
+                QueryParam::new("b", "2"),
+            ]))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_is_generated_with_form_data_body() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/a http/1.1
+            Content-Type: multipart/form-data; boundary=----PostresFormBoundary7MA4YWxkTrZu0gW
+
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="title"
+
+            hello
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="image"; filename="image.png"
+
+            < /tmp/image.png
+            ------PostresFormBoundary7MA4YWxkTrZu0gW--
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/a")
+            // the content type without boundary is replaced by the one the body requires
+            .headers(vec![Header {
+                enabled: true,
+                name: "content-type".to_string(),
+                value: "multipart/form-data".to_string(),
+            }])
+            .body(Body::FormData(vec![
+                FormDataParamSpec {
+                    content_type: None,
+                    enabled: true,
+                    name: "title".to_string(),
+                    value: FormParamValue::Text("hello".to_string()),
+                },
+                FormDataParamSpec {
+                    content_type: None,
+                    enabled: true,
+                    name: "image".to_string(),
+                    value: FormParamValue::File(vec!["/tmp/image.png".to_string()]),
+                },
+            ]))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_is_generated_with_graphql_body() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/graphql http/1.1
+            X-REQUEST-TYPE: GraphQL
+
+            query ($id: ID!) { user(id: $id) { name } }
+
+            {"id": "1"}
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/graphql")
+            .body(Body::Graphql(GraphqlSpec {
+                spec: "query ($id: ID!) { user(id: $id) { name } }".to_string(),
+                variables: Some("{\"id\": \"1\"}".to_string()),
+                operation_name: None,
+            }))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn http_request_with_graphql_operation_name_is_sent_as_json() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/graphql http/1.1
+            Content-Type: application/json
+
+            {
+                "query": "query User($id: ID!) { user(id: $id) { name } }\nquery Me { me { name } }",
+                "operationName": "User",
+                "variables": {
+                  "id": "{{id}}"
+                }
+            }
+        "#};
+        let req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/graphql")
+            .headers(vec![Header {
+                enabled: true,
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            }])
+            .body(Body::Graphql(GraphqlSpec {
+                spec: "query User($id: ID!) { user(id: $id) { name } }\nquery Me { me { name } }"
+                    .to_string(),
+                variables: Some("{\n  \"id\": \"{{id}}\"\n}".to_string()),
+                operation_name: Some("User".to_string()),
+            }))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.to_restclient(), result);
+    }
+
+    #[test]
+    fn disabled_entries_are_written_as_comments() {
+        let result = indoc! {r#"
+            #####
+            # @name testReq
+
+            POST http://127.0.0.1:3000/login
+                ?lang=en
+                # &debug=true
+            # X-Trace: on
+            Accept: */*
+            # &remember=true
+
+            user=me
+            &password=secret
+        "#};
+        let header = |enabled: bool, name: &str, value: &str| Header {
+            enabled,
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let mut req = HttpRequestBuilder::default()
+            .name("testReq")
+            .method(Method::Post)
+            .url("http://127.0.0.1:3000/login?lang=en")
+            .headers(vec![
+                header(false, "X-Trace", "on"),
+                header(true, "Accept", "*/*"),
+            ])
+            .body(Body::UrlEncoded(vec![
+                QueryParam::new("user", "me"),
+                QueryParam::disabled("remember", "true"),
+                QueryParam::new("password", "secret"),
+            ]))
+            .build()
+            .unwrap();
+        req.add_query_param(QueryParam::disabled("debug", "true"));
+        // a disabled header does not keep another one from being added
+        assert!(req.add_header_if_absent(header(true, "X-Trace", "off")));
+        req.headers.pop();
+
+        assert_eq!(req.to_restclient(), result);
+    }
 }
@@ -0,0 +1,208 @@
+//! makes the files requests read their bodies from reachable from the http files
+//! Postman records file paths as they were on the machine of whoever exported the collection, often absolute paths like /home/someone/files/avatar.png.
+//! Files are looked for in a base directory and referenced relative to the http file using them, so the converted workspace can be moved around.
+//! If asked to, files are also copied to a directory next to the output, making it self-contained
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
+
+use super::{check_overwrite, has_drive, HttpRequest};
+use crate::{config::OverwritePolicy, error::PostresError, report::Report};
+
+/// files referenced by the converted requests, and where they are copied to, if they are
+pub(crate) struct Assets {
+    /// where files with relative paths, or absolute paths not found on this machine, are looked for
+    base_dir: PathBuf,
+    /// directory receiving a copy of every file found
+    copy_to: Option<PathBuf>,
+    /// files to be copied, by where they are copied to
+    copies: BTreeMap<PathBuf, PathBuf>,
+}
+
+impl Assets {
+    pub(crate) fn new(base_dir: impl Into<PathBuf>, copy_to: Option<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+            copy_to,
+            copies: BTreeMap::new(),
+        }
+    }
+
+    /// rewrites the file paths of the request relative to http_dir, the directory of the http file the request is written to
+    /// files which can not be found are reported and kept as they are
+    pub(crate) fn resolve(
+        &mut self,
+        request: &mut HttpRequest,
+        http_dir: &Path,
+        report: &mut Report,
+    ) {
+        let mut report = report.for_item(request.source().origin.clone());
+        request.rewrite_files(|path| {
+            // the file changes with the environment, there is no way to tell which one is meant
+            if path.contains("{{") {
+                report.warning(format!(
+                    "file {path} references variables, its path is kept as it is"
+                ));
+                return path.to_string();
+            }
+            let found = match self.locate(path) {
+                Some(found) => found,
+                None => {
+                    report.warning(format!(
+                        "file {path} not found in {}, its path is kept as it is",
+                        self.base_dir.display()
+                    ));
+                    return path.to_string();
+                }
+            };
+            let target = match &self.copy_to {
+                Some(dir) => self.copy_destination(found, dir.clone()),
+                None => found,
+            };
+            relative_path(&absolute(http_dir), &absolute(&target))
+        });
+    }
+
+    /// the files copy writes
+    pub(crate) fn destinations(&self) -> Vec<PathBuf> {
+        self.copies().map(|(dest, _)| dest.clone()).collect()
+    }
+
+    /// copies every file found to the assets directory
+    /// nothing is copied if some copy would replace an existing file the user did not ask to overwrite
+    pub(crate) fn copy(&self, policy: OverwritePolicy) -> Result<(), PostresError> {
+        check_overwrite(&self.destinations(), policy)?;
+        for (dest, source) in self.copies() {
+            if let Some(dir) = dest.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| PostresError::DestFileError { msg: e.to_string() })?;
+            }
+            fs::copy(source, dest).map_err(|e| PostresError::DestFileError {
+                msg: format!("{} could not be copied. {e}", source.display()),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// a file already in the assets directory, as when converting again, is left alone
+    fn copies(&self) -> impl Iterator<Item = (&PathBuf, &PathBuf)> {
+        self.copies
+            .iter()
+            .filter(|(dest, source)| absolute(dest) != absolute(source))
+    }
+
+    /// where the file is on this machine
+    /// absolute paths are tried as they are and, failing that, by their name in the base directory, where files exported from another machine are expected to be
+    fn locate(&self, path: &str) -> Option<PathBuf> {
+        let is_absolute = Path::new(path).is_absolute() || path.starts_with('/') || has_drive(path);
+        let candidates = if is_absolute {
+            vec![PathBuf::from(path), self.base_dir.join(file_name(path))]
+        } else {
+            vec![self.base_dir.join(path)]
+        };
+        candidates.into_iter().find(|c| c.is_file())
+    }
+
+    /// the copy of the file in dir. Different files with the same name get numbered copies: avatar.png, avatar-2.png
+    fn copy_destination(&mut self, source: PathBuf, dir: PathBuf) -> PathBuf {
+        let source = absolute(&source);
+        if let Some((dest, _)) = self.copies.iter().find(|(_, s)| **s == source) {
+            return dest.clone();
+        }
+        let name = file_name(&source.to_string_lossy());
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+            _ => (name.as_str(), String::new()),
+        };
+        let dest = (1..)
+            .map(|i| match i {
+                1 => dir.join(&name),
+                i => dir.join(format!("{stem}-{i}{extension}")),
+            })
+            .find(|dest| !self.copies.contains_key(dest))
+            .unwrap();
+        self.copies.insert(dest.clone(), source);
+        dest
+    }
+}
+
+/// the last segment of the path, whatever the system it was recorded on
+fn file_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+}
+
+/// the path from the current directory, with . and .. resolved without touching the file system, so that paths to files not yet written can be compared
+fn absolute(path: &Path) -> PathBuf {
+    let path = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => env::current_dir().unwrap_or_default().join(path),
+    };
+    path.components()
+        .fold(PathBuf::new(), |mut absolute, component| {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    absolute.pop();
+                }
+                c => absolute.push(c),
+            }
+            absolute
+        })
+}
+
+/// how to get to a file from a directory, both absolute, written with / as RestClient expects on any system
+/// paths with nothing in common, as in different windows drives, can only be reached through the absolute path
+fn relative_path(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<_> = from_dir.components().collect();
+    let to_components: Vec<_> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return to.display().to_string();
+    }
+    let parents = (common..from.len()).map(|_| "..".to_string());
+    let rest = to_components[common..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy().to_string());
+    parents.chain(rest).collect::<Vec<_>>().join("/")
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_find_the_way_from_a_directory_to_a_file() {
+        let path = |from: &str, to: &str| relative_path(Path::new(from), Path::new(to));
+        assert_eq!(path("/work/out", "/work/out/body.json"), "body.json");
+        assert_eq!(
+            path("/work/out/users", "/work/files/a.png"),
+            "../../files/a.png"
+        );
+        assert_eq!(path("/work/out", "/work/out/assets/a.png"), "assets/a.png");
+        assert_eq!(
+            absolute(Path::new("/work/./out/../files/a.png")),
+            PathBuf::from("/work/files/a.png")
+        );
+    }
+
+    #[test]
+    fn should_give_different_files_with_the_same_name_different_copies() {
+        let mut assets = Assets::new("/files", Some(PathBuf::from("/out/assets")));
+        let mut copy = |source: &str| assets.copy_destination(source.into(), "/out/assets".into());
+        assert_eq!(copy("/files/a.png"), PathBuf::from("/out/assets/a.png"));
+        assert_eq!(copy("/files/b/a.png"), PathBuf::from("/out/assets/a-2.png"));
+        assert_eq!(copy("/files/a.png"), PathBuf::from("/out/assets/a.png"));
+        assert_eq!(copy("/files/.env"), PathBuf::from("/out/assets/.env"));
+        assert_eq!(copy("/files/c/.env"), PathBuf::from("/out/assets/.env-2"));
+    }
+}
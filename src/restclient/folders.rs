@@ -0,0 +1,179 @@
+//! writes requests one http file per postman folder, in directories mirroring the folders
+//! A folder's file is named after it and sits in its parent's directory. Its subfolders go in a directory with the same name:
+//! Users.http holds the requests of the Users folder, Users/Admin.http the ones of Users/Admin.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{check_overwrite, http_file, save_to_path, HttpRequest, Variable};
+use crate::{config::OverwritePolicy, error::PostresError, tree::Node};
+
+/// the requests found directly in a postman folder. The root folder stands for the collection itself
+#[derive(Debug)]
+pub(crate) struct HttpFolder {
+    pub name: String,
+    pub requests: Vec<HttpRequest>,
+}
+
+/// records one http file per folder with requests under dir. Every file declares all variables, so that each one works on its own
+/// nothing is written if any of the files exists and the policy does not allow it to be replaced
+pub(crate) fn save_folders(
+    dir: &str,
+    root: &Node<HttpFolder>,
+    variables: &[Variable],
+    policy: OverwritePolicy,
+) -> Result<(), PostresError> {
+    let mut files = BTreeMap::new();
+    collect_files(root, Path::new(dir), &mut files);
+    check_overwrite(&files.keys().cloned().collect::<Vec<_>>(), policy)?;
+    for (path, requests) in files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| PostresError::DestFileError { msg: e.to_string() })?;
+        }
+        save_to_path(
+            &path.to_string_lossy(),
+            &http_file(variables, requests.into_iter()),
+            policy,
+        )?;
+    }
+    Ok(())
+}
+
+/// the http files save_folders writes under dir
+pub(crate) fn folder_files(dir: &str, root: &Node<HttpFolder>) -> Vec<PathBuf> {
+    let mut files = BTreeMap::new();
+    collect_files(root, Path::new(dir), &mut files);
+    files.into_keys().collect()
+}
+
+/// the directory of the http file holding the requests of a folder, given the names of the folder and of the ones enclosing it, from the outermost
+/// requests outside any folder go in the collection file, directly in dir
+pub(crate) fn folder_dir(dir: &str, folder: &[String]) -> PathBuf {
+    let enclosing = &folder[..folder.len().saturating_sub(1)];
+    enclosing
+        .iter()
+        .fold(PathBuf::from(dir), |path, name| path.join(file_name(name)))
+}
+
+/// the requests, already in RestClient format, of every file to be written
+/// folders whose names only differ by characters not allowed in file names end up in the same file
+fn collect_files(node: &Node<HttpFolder>, dir: &Path, files: &mut BTreeMap<PathBuf, Vec<String>>) {
+    let value = node.get_value();
+    let folder = match value.as_ref() {
+        Some(f) => f,
+        None => return,
+    };
+    let file_name = file_name(&folder.name);
+    if !folder.requests.is_empty() {
+        files
+            .entry(dir.join(format!("{file_name}.http")))
+            .or_default()
+            .extend(folder.requests.iter().map(HttpRequest::to_restclient));
+    }
+    // the root folder is the collection: its subfolders go directly in dir
+    let children_dir = if node.is_root() {
+        dir.to_path_buf()
+    } else {
+        dir.join(&file_name)
+    };
+    let mut child = node.get_first_child();
+    while let Some(c) = child {
+        collect_files(&c, &children_dir, files);
+        child = c.get_next();
+    }
+}
+
+/// a postman name made safe to be used as a file or directory name on any system
+fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    // windows does not allow names ending in dots or spaces, and . or .. would not be folders at all
+    let name = name.trim().trim_end_matches('.');
+    if name.is_empty() {
+        "unnamed".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::restclient::{HttpRequestBuilder, Method};
+
+    fn request(name: &str) -> HttpRequest {
+        HttpRequestBuilder::default()
+            .name(name)
+            .method(Method::Get)
+            .url("http://host")
+            .build()
+            .unwrap()
+    }
+
+    fn folder(name: &str, requests: &[&str]) -> Node<HttpFolder> {
+        Node::create_leaf(HttpFolder {
+            name: name.to_string(),
+            requests: requests.iter().map(|r| request(r)).collect(),
+        })
+    }
+
+    #[test]
+    fn should_place_folder_files_next_to_their_subfolders() {
+        let root = folder("my collection", &["root"]);
+        let users = folder("Users", &["list"]);
+        users.add_node(folder("Admin", &["grant"]));
+        users.add_node(folder("empty", &[]));
+        root.add_node(users);
+        root.add_node(folder("a/b", &["x"]));
+        root.add_node(folder("a:b", &["y"]));
+        let mut files = BTreeMap::new();
+        collect_files(&root, Path::new("out"), &mut files);
+        let files: Vec<_> = files
+            .into_iter()
+            .map(|(path, requests)| (path, requests.len()))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (PathBuf::from("out/Users/Admin.http"), 1),
+                (PathBuf::from("out/Users.http"), 1),
+                (PathBuf::from("out/a-b.http"), 2),
+                (PathBuf::from("out/my collection.http"), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_tell_where_the_file_of_a_folder_is() {
+        let folder = |names: &[&str]| {
+            folder_dir(
+                "out",
+                &names.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(folder(&[]), PathBuf::from("out"));
+        assert_eq!(folder(&["Users"]), PathBuf::from("out"));
+        assert_eq!(folder(&["Users", "Admin"]), PathBuf::from("out/Users"));
+        assert_eq!(folder(&["a/b", "c", "d"]), PathBuf::from("out/a-b/c"));
+    }
+
+    #[test]
+    fn should_make_names_safe_for_file_systems() {
+        assert_eq!(file_name("Users / Admin?"), "Users - Admin-");
+        assert_eq!(file_name(" .. "), "unnamed");
+        assert_eq!(file_name("v1."), "v1");
+    }
+}
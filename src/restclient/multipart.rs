@@ -0,0 +1,168 @@
+//! writes multipart/form-data bodies
+//! Each text field and each file is a part of its own. A field holding several files is sent as several parts with the same name, as browsers do.
+//! RestClient reads the content of file parts from disk when their content is a line starting with <, so only paths are written
+
+use super::{file_reference, FormDataParamSpec, FormParamValue};
+
+/// boundary separating parts of a multipart body, unless some part contains it
+const BOUNDARY: &str = "----PostresFormBoundary7MA4YWxkTrZu0gW";
+
+/// a boundary no part of the form contains, always the same for the same form
+/// file contents can not be checked, they are read by RestClient when the request is sent. The base boundary is unlikely enough to be found in them
+pub(super) fn boundary(params: &[FormDataParamSpec]) -> String {
+    let texts: Vec<&str> = params
+        .iter()
+        .filter(|p| p.enabled)
+        .flat_map(|p| {
+            let values = match &p.value {
+                FormParamValue::Text(text) => vec![text.as_str()],
+                FormParamValue::File(files) => files.iter().map(String::as_str).collect(),
+            };
+            values
+                .into_iter()
+                .chain([p.name.as_str()])
+                .chain(p.content_type.as_deref())
+        })
+        .collect();
+    let collides = |boundary: &str| texts.iter().any(|t| t.contains(boundary));
+    if !collides(BOUNDARY) {
+        return BOUNDARY.to_string();
+    }
+    (2..)
+        .map(|i| format!("{BOUNDARY}{i}"))
+        .find(|b| !collides(b))
+        .unwrap()
+}
+
+/// the body of a multipart request whose content type declares the given boundary
+pub(super) fn to_restclient(params: &[FormDataParamSpec], boundary: &str) -> String {
+    let mut builder = string_builder::Builder::default();
+    // disabled fields are not sent, they are written as comments before the body
+    for param in params.iter().filter(|p| p.enabled) {
+        let parts = match &param.value {
+            FormParamValue::Text(text) => vec![(None, text.clone())],
+            FormParamValue::File(files) => files
+                .iter()
+                .map(|f| (Some(file_name(f)), format!("< {}", file_reference(f))))
+                .collect(),
+        };
+        for (file_name, content) in parts {
+            builder.append(format!("--{boundary}\n"));
+            builder.append(format!(
+                "Content-Disposition: form-data; name=\"{}\"",
+                quoted(&param.name)
+            ));
+            if let Some(file_name) = file_name {
+                builder.append(format!("; filename=\"{}\"", quoted(&file_name)));
+            }
+            builder.append("\n");
+            if let Some(content_type) = &param.content_type {
+                builder.append(format!("Content-Type: {content_type}\n"));
+            }
+            builder.append(format!("\n{content}\n"));
+        }
+    }
+    builder.append(format!("--{boundary}--"));
+    builder.string().unwrap()
+}
+
+/// the name the server is told the file has: the last segment of its path, whatever the system it was recorded on
+fn file_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+}
+
+/// names and file names are written between quotes, which they can not contain. Browsers escape them the same way
+fn quoted(text: &str) -> String {
+    text.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn text(name: &str, value: &str, content_type: Option<&str>) -> FormDataParamSpec {
+        FormDataParamSpec {
+            content_type: content_type.map(str::to_string),
+            enabled: true,
+            name: name.to_string(),
+            value: FormParamValue::Text(value.to_string()),
+        }
+    }
+
+    fn files(name: &str, paths: &[&str], content_type: Option<&str>) -> FormDataParamSpec {
+        FormDataParamSpec {
+            content_type: content_type.map(str::to_string),
+            enabled: true,
+            name: name.to_string(),
+            value: FormParamValue::File(paths.iter().map(|p| p.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn should_write_mixed_text_and_file_parts() {
+        let params = vec![
+            text("title", "hello", None),
+            text("meta", r#"{"tags": ["a"]}"#, Some("application/json")),
+            files("avatar", &["images/me.png"], Some("image/png")),
+            files(
+                "attachments",
+                &["./docs/a.pdf", "C:\\Users\\me\\b.txt", "/tmp/c.csv"],
+                None,
+            ),
+            text("say \"hi\"", "", None),
+        ];
+        let boundary = boundary(&params);
+        assert_eq!(
+            to_restclient(&params, &boundary),
+            indoc! {r#"
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="title"
+
+                hello
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="meta"
+                Content-Type: application/json
+
+                {"tags": ["a"]}
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="avatar"; filename="me.png"
+                Content-Type: image/png
+
+                < ./images/me.png
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="attachments"; filename="a.pdf"
+
+                < ./docs/a.pdf
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="attachments"; filename="b.txt"
+
+                < C:\Users\me\b.txt
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="attachments"; filename="c.csv"
+
+                < /tmp/c.csv
+                ------PostresFormBoundary7MA4YWxkTrZu0gW
+                Content-Disposition: form-data; name="say %22hi%22"
+
+
+                ------PostresFormBoundary7MA4YWxkTrZu0gW--"#}
+        );
+    }
+
+    #[test]
+    fn should_pick_a_boundary_no_part_contains() {
+        assert_eq!(boundary(&[text("a", "b", None)]), BOUNDARY);
+        let params = vec![
+            text("a", &format!("--{BOUNDARY}--"), None),
+            files("b", &[&format!("{BOUNDARY}2.txt")], None),
+        ];
+        assert_eq!(boundary(&params), format!("{BOUNDARY}3"));
+    }
+}
@@ -0,0 +1,146 @@
+//! RestClient reads environments from the rest-client.environmentVariables key of the vscode settings.json file
+
+use std::{fs, path::Path};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::{save_to_path, Environment};
+use crate::{config::OverwritePolicy, error::PostresError};
+
+const ENVIRONMENTS_KEY: &str = "rest-client.environmentVariables";
+
+/// records environments in a vscode settings file
+/// the settings file is likely to hold much more than RestClient environments, so everything already there is kept.
+/// Environments and variables not touched by the conversion are also kept. Variables present in both are updated.
+pub(crate) fn save_environments(
+    path: &str,
+    environments: &[Environment],
+) -> Result<(), PostresError> {
+    let error = |msg: String| PostresError::SettingsFileError {
+        path: path.to_string(),
+        msg,
+    };
+    let mut settings = load_settings(path).map_err(&error)?;
+    merge_environments(&mut settings, environments).map_err(&error)?;
+    if let Some(dir) = Path::new(path)
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+    {
+        fs::create_dir_all(dir).map_err(|e| error(e.to_string()))?;
+    }
+    save_to_path(
+        path,
+        &to_settings_json(&settings).map_err(&error)?,
+        OverwritePolicy::Overwrite,
+    )
+}
+
+fn load_settings(path: &str) -> Result<Map<String, Value>, String> {
+    if !Path::new(path).exists() {
+        return Ok(Map::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    // vscode accepts comments in settings files, serde_json does not.
+    // Rather than risking to destroy something we do not understand, we refuse to touch the file.
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(settings)) => Ok(settings),
+        Ok(_) => Err("settings file does not contain a json object".to_string()),
+        Err(e) => Err(format!("could not parse settings file ({e}). Comments are not supported, please remove them or merge environments by hand")),
+    }
+}
+
+fn merge_environments(
+    settings: &mut Map<String, Value>,
+    environments: &[Environment],
+) -> Result<(), String> {
+    let existing = settings
+        .entry(ENVIRONMENTS_KEY)
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or_else(|| format!("{ENVIRONMENTS_KEY} is not a json object"))?;
+    for environment in environments {
+        let variables = existing
+            .entry(environment.name.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| format!("environment {} is not a json object", environment.name))?;
+        for variable in &environment.variables {
+            variables.insert(variable.name.clone(), Value::String(variable.value.clone()));
+        }
+    }
+    Ok(())
+}
+
+// vscode formats its settings files with 4 spaces, so do we
+fn to_settings_json(settings: &Map<String, Value>) -> Result<String, String> {
+    let mut content = vec![];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    settings
+        .serialize(&mut serializer)
+        .map_err(|e| e.to_string())?;
+    let mut content = String::from_utf8(content).map_err(|e| e.to_string())?;
+    content.push('\n');
+    Ok(content)
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::super::Variable;
+    use super::*;
+
+    #[test]
+    fn should_merge_environments_keeping_existing_settings() {
+        let mut settings = json!({
+            "editor.tabSize": 2,
+            "rest-client.environmentVariables": {
+                "local": { "host": "localhost" },
+                "dev": { "host": "old", "user": "me" }
+            }
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        merge_environments(
+            &mut settings,
+            &[Environment {
+                name: "dev".to_string(),
+                variables: vec![Variable {
+                    description: None,
+                    enabled: true,
+                    name: "host".to_string(),
+                    value: "dev.example.com".to_string(),
+                }],
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Object(settings),
+            json!({
+                "editor.tabSize": 2,
+                "rest-client.environmentVariables": {
+                    "local": { "host": "localhost" },
+                    "dev": { "host": "dev.example.com", "user": "me" }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn should_refuse_to_merge_into_unexpected_settings() {
+        let mut settings = json!({ "rest-client.environmentVariables": [] })
+            .as_object()
+            .unwrap()
+            .clone();
+        assert!(merge_environments(&mut settings, &[]).is_err());
+    }
+}
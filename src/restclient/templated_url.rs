@@ -0,0 +1,274 @@
+//! urls as they appear in postman collections and http files, which are not necessarily valid urls
+
+use std::fmt;
+
+/// an url which might contain {{variables}} anywhere, including its scheme, host and port
+/// Because variables are only replaced when the request is sent, such urls can not be handled by the url crate: {{baseUrl}}/users is not a valid absolute url.
+/// This splits the url into its parts without validating them, so that writing the parts back yields exactly the original text.
+/// Nothing is decoded: percent encoded sequences are kept as they are.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TemplatedUrl {
+    /// E.g: http, https or {{protocol}}, without the ://
+    pub scheme: Option<String>,
+    /// everything in the authority before the port, including user info if present
+    pub host: Option<String>,
+    /// the text after the last : of the authority. Might be empty if the url ends its authority with :
+    pub port: Option<String>,
+    /// the path, including its leading /
+    pub path: String,
+    /// the query string, without the ?
+    pub query: Option<String>,
+    /// the fragment, without the #
+    pub fragment: Option<String>,
+}
+
+impl TemplatedUrl {
+    /// splits an url into its parts
+    /// urls without a scheme are assumed to start with their host, unless they start with /, as postman accepts localhost:3000/users or {{baseUrl}}/users
+    pub fn parse(url: &str) -> Self {
+        let mut res = Self::default();
+        let mut rest = url;
+        // the scheme is whatever comes before the first ://, as long as no other delimiter comes first
+        let delimiter = find_outside_placeholders(rest, 0, |c| matches!(c, '/' | '?' | '#' | ':'));
+        if let Some(p) = delimiter.filter(|p| *p > 0 && rest[*p..].starts_with("://")) {
+            res.scheme = Some(rest[..p].to_string());
+            rest = &rest[p + 3..];
+        }
+        if res.scheme.is_some() || !rest.starts_with('/') {
+            let end = find_outside_placeholders(rest, 0, |c| matches!(c, '/' | '?' | '#'))
+                .unwrap_or(rest.len());
+            let (host, port) = split_port(&rest[..end]);
+            if res.scheme.is_some() || !host.is_empty() || port.is_some() {
+                res.host = Some(host.to_string());
+                res.port = port.map(|p| p.to_string());
+            }
+            rest = &rest[end..];
+        }
+        if let Some(p) = find_outside_placeholders(rest, 0, |c| c == '#') {
+            res.fragment = Some(rest[p + 1..].to_string());
+            rest = &rest[..p];
+        }
+        if let Some(p) = find_outside_placeholders(rest, 0, |c| c == '?') {
+            res.query = Some(rest[p + 1..].to_string());
+            rest = &rest[..p];
+        }
+        res.path = rest.to_string();
+        res
+    }
+
+    /// the query string split into name and value pairs, kept exactly as they were written
+    /// a parameter without = has an empty value
+    pub fn query_params(&self) -> Vec<(String, String)> {
+        let query = match &self.query {
+            Some(q) => q,
+            None => return vec![],
+        };
+        split_outside_placeholders(query, '&')
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .map(|p| match find_outside_placeholders(p, 0, |c| c == '=') {
+                Some(e) => (p[..e].to_string(), p[e + 1..].to_string()),
+                None => (p.to_string(), String::new()),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for TemplatedUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{scheme}://")?;
+        }
+        if let Some(host) = &self.host {
+            f.write_str(host)?;
+        }
+        if let Some(port) = &self.port {
+            write!(f, ":{port}")?;
+        }
+        f.write_str(&self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// splits host and port at the last : of the authority
+/// a : inside a variable, inside an ipv6 address or before the user info @ does not start a port
+fn split_port(authority: &str) -> (&str, Option<&str>) {
+    let start = [
+        rfind_outside_placeholders(authority, '@'),
+        rfind_outside_placeholders(authority, ']'),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .map(|p| p + 1)
+    .unwrap_or(0);
+    let colon = split_outside_placeholders(&authority[start..], ':');
+    if colon.len() < 2 {
+        return (authority, None);
+    }
+    let port = colon[colon.len() - 1];
+    let host_end = authority.len() - port.len() - 1;
+    (&authority[..host_end], Some(port))
+}
+
+/// byte position of the first char matching the predicate which is not inside a {{variable}}
+fn find_outside_placeholders(
+    text: &str,
+    from: usize,
+    pred: impl Fn(char) -> bool,
+) -> Option<usize> {
+    let mut i = from;
+    while i < text.len() {
+        if text[i..].starts_with("{{") {
+            // a {{ which is never closed is just text
+            if let Some(close) = text[i + 2..].find("}}") {
+                i += close + 4;
+                continue;
+            }
+        }
+        let c = text[i..].chars().next().unwrap();
+        if pred(c) {
+            return Some(i);
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+fn rfind_outside_placeholders(text: &str, needle: char) -> Option<usize> {
+    let mut found = None;
+    let mut from = 0;
+    while let Some(p) = find_outside_placeholders(text, from, |c| c == needle) {
+        found = Some(p);
+        from = p + needle.len_utf8();
+    }
+    found
+}
+
+fn split_outside_placeholders(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    while let Some(p) = find_outside_placeholders(text, start, |c| c == separator) {
+        parts.push(&text[start..p]);
+        start = p + separator.len_utf8();
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/*
+    see 009
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(
+        scheme: Option<&str>,
+        host: Option<&str>,
+        port: Option<&str>,
+        path: &str,
+        query: Option<&str>,
+        fragment: Option<&str>,
+    ) -> TemplatedUrl {
+        TemplatedUrl {
+            scheme: scheme.map(str::to_string),
+            host: host.map(str::to_string),
+            port: port.map(str::to_string),
+            path: path.to_string(),
+            query: query.map(str::to_string),
+            fragment: fragment.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn should_split_url_into_parts() {
+        assert_eq!(
+            TemplatedUrl::parse("http://127.0.0.1:3000/a/b?c=1&d=2#top"),
+            url(
+                Some("http"),
+                Some("127.0.0.1"),
+                Some("3000"),
+                "/a/b",
+                Some("c=1&d=2"),
+                Some("top")
+            )
+        );
+        assert_eq!(
+            TemplatedUrl::parse("{{baseUrl}}/users?id=1"),
+            url(
+                None,
+                Some("{{baseUrl}}"),
+                None,
+                "/users",
+                Some("id=1"),
+                None
+            )
+        );
+        assert_eq!(
+            TemplatedUrl::parse("{{protocol}}://{{host}}:{{port}}/a"),
+            url(
+                Some("{{protocol}}"),
+                Some("{{host}}"),
+                Some("{{port}}"),
+                "/a",
+                None,
+                None
+            )
+        );
+        assert_eq!(
+            TemplatedUrl::parse("https://user:pass@[::1]/a"),
+            url(
+                Some("https"),
+                Some("user:pass@[::1]"),
+                None,
+                "/a",
+                None,
+                None
+            )
+        );
+        assert_eq!(
+            TemplatedUrl::parse("/a/{{b?c}}?d={{e#f}}"),
+            url(None, None, None, "/a/{{b?c}}", Some("d={{e#f}}"), None)
+        );
+    }
+
+    #[test]
+    fn should_write_back_exactly_what_was_parsed() {
+        for raw in [
+            "http://127.0.0.1:3000/a/b?c=1&d=2#top",
+            "{{baseUrl}}/users?id=1",
+            "{{baseUrl}}",
+            "localhost:3000",
+            "http://host:",
+            "file:///etc/hosts",
+            "//host/a",
+            "?a=1",
+            "#frag",
+            "",
+            "http://h/a%20b?q=%2F%26&x",
+            "http://h/{{unclosed",
+        ] {
+            assert_eq!(TemplatedUrl::parse(raw).to_string(), raw);
+        }
+    }
+
+    #[test]
+    fn should_split_query_params_without_decoding_them() {
+        assert_eq!(
+            TemplatedUrl::parse("{{baseUrl}}/a?q=%2F%26&flag&&v={{a&b}}&e=x=y").query_params(),
+            vec![
+                ("q".to_string(), "%2F%26".to_string()),
+                ("flag".to_string(), String::new()),
+                ("v".to_string(), "{{a&b}}".to_string()),
+                ("e".to_string(), "x=y".to_string()),
+            ]
+        );
+    }
+}
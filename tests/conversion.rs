@@ -0,0 +1,779 @@
+//! end to end tests: postman collection files in tests/fixtures are converted and the recorded http files are checked
+
+use std::{fs, path::PathBuf};
+
+use indoc::indoc;
+use postres::{
+    AuditConfig, Config, ConversionOptions, Feature, NamingStyle, OverwritePolicy, ParsingMode,
+    PostresError, UpgradeConfig,
+};
+
+/// creates an empty directory for a test to record its files
+/// each test uses its own directory, so that tests can run in parallel
+fn scratch_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("postres-{}-{test_name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn config_for(fixture_name: &str, dest: &PathBuf) -> Config {
+    Config {
+        dest_file: dest.to_string_lossy().to_string(),
+        source_file: fixture(fixture_name),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn should_write_http_file_with_all_requests_in_order() {
+    let dest = scratch_dir("in_order").join("out.http");
+    postres::postman_file_to_http_file(config_for(
+        "string_requests.postman_collection.json",
+        &dest,
+    ))
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name list-users
+
+            GET http://127.0.0.1:3000/users http/1.1
+
+            #####
+            # @name Admin_get-user
+
+            GET http://127.0.0.1:3000/admin/users/{{id}} http/1.1
+        "#}
+    );
+}
+
+#[test]
+fn should_write_headers_query_params_and_bodies_of_structured_requests() {
+    let dest = scratch_dir("structured").join("out.http");
+    postres::postman_file_to_http_file(config_for(
+        "structured_requests.postman_collection.json",
+        &dest,
+    ))
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name Users_search-users
+
+            GET http://127.0.0.1:3000/users
+                ?name=ann
+                &page=2 http/1.1
+            Accept: application/json
+
+            #####
+            # @name Users_create-user
+
+            POST http://127.0.0.1:3000/users http/1.1
+            Content-Type: application/json
+
+            {
+                "name": "ann"
+            }
+
+            #####
+            # @name Users_login
+
+            POST http://127.0.0.1:3000/login http/1.1
+            Content-Type: application/x-www-form-urlencoded
+
+            user=ann
+            &password=secret
+        "#}
+    );
+}
+
+#[test]
+fn should_write_collection_and_folder_variables_as_file_variables() {
+    let dest = scratch_dir("variables").join("out.http");
+    postres::postman_file_to_http_file(config_for("variables.postman_collection.json", &dest))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            @baseUrl = http://127.0.0.1:3000
+            @id = 1
+            @verbose = true
+            @filter = {"active":true}
+            # @legacyUrl = http://127.0.0.1:8080
+            @Users_id = 42
+
+            #####
+            # @name Users_get-user
+
+            GET {{baseUrl}}/users/{{Users_id}} http/1.1
+
+            #####
+            # @name get-order
+
+            GET {{baseUrl}}/orders/{{id}} http/1.1
+        "#}
+    );
+}
+
+#[test]
+fn should_apply_authorization_inherited_from_collection_and_folders() {
+    let dest = scratch_dir("auth").join("out.http");
+    postres::postman_file_to_http_file(config_for("auth.postman_collection.json", &dest)).unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name Internal_list-users
+
+            GET http://127.0.0.1:3000/users http/1.1
+            Authorization: Basic admin:{{password}}
+
+            #####
+            # @name Internal_health
+
+            GET http://127.0.0.1:3000/health http/1.1
+
+            #####
+            # @name get-order
+
+            GET http://127.0.0.1:3000/orders/1 http/1.1
+            Authorization: Bearer {{token}}
+
+            #####
+            # @name search
+
+            GET http://127.0.0.1:3000/search
+                ?q=x
+                &api_key=abc http/1.1
+        "#}
+    );
+}
+
+#[test]
+fn should_declare_path_variable_defaults_per_request() {
+    let dest = scratch_dir("path_variables").join("out.http");
+    postres::postman_file_to_http_file(config_for("path_variables.postman_collection.json", &dest))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            # id of an existing user
+            @get-user_id = 42
+            @get-order_id = 7
+
+            #####
+            # @name get-user
+
+            GET {{baseUrl}}/users/{{get-user_id}} http/1.1
+
+            #####
+            # @name get-order
+
+            GET {{baseUrl}}/orders/{{get-order_id}}
+                ?expand=items http/1.1
+        "#}
+    );
+}
+
+#[test]
+fn should_convert_v2_0_0_collections() {
+    let dest = scratch_dir("v2_0_0").join("out.http");
+    postres::postman_file_to_http_file(config_for("v2_0_0.postman_collection.json", &dest))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name Users_list-users
+
+            GET http://127.0.0.1:3000/users http/1.1
+            Authorization: Bearer {{token}}
+        "#}
+    );
+}
+
+#[test]
+fn should_convert_v1_collections() {
+    let dest = scratch_dir("v1").join("out.http");
+    postres::postman_file_to_http_file(config_for("v1.postman_collection.json", &dest)).unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name Users_create-user
+
+            POST http://127.0.0.1:3000/users http/1.1
+            Content-Type: application/json
+
+            {"name": "a"}
+
+            #####
+            # @name health
+
+            GET http://127.0.0.1:3000/health http/1.1
+        "#}
+    );
+}
+
+#[test]
+fn should_upgrade_old_collections_to_v2_1_0() {
+    let dir = scratch_dir("upgrade");
+    for (fixture_name, upgraded) in [
+        ("v1.postman_collection.json", dir.join("v1.json")),
+        ("v2_0_0.postman_collection.json", dir.join("v2_0_0.json")),
+    ] {
+        postres::upgrade_postman_file(UpgradeConfig {
+            dest_file: upgraded.to_string_lossy().to_string(),
+            source_file: fixture(fixture_name),
+            ..Default::default()
+        })
+        .unwrap();
+        let content = fs::read_to_string(&upgraded).unwrap();
+        assert!(content.contains("collection/v2.1.0/collection.json"));
+        assert!(!content.contains("null"));
+        // upgrading must not change what the collection does
+        let from_fixture = dir.join("from_fixture.http");
+        let from_upgraded = dir.join("from_upgraded.http");
+        for (source, dest) in [
+            (&fixture(fixture_name), &from_fixture),
+            (&upgraded.to_string_lossy().to_string(), &from_upgraded),
+        ] {
+            postres::postman_file_to_http_file(Config {
+                dest_file: dest.to_string_lossy().to_string(),
+                source_file: source.clone(),
+                overwrite: OverwritePolicy::Overwrite,
+                ..Default::default()
+            })
+            .unwrap();
+        }
+        assert_eq!(
+            fs::read_to_string(&from_upgraded).unwrap(),
+            fs::read_to_string(&from_fixture).unwrap()
+        );
+        // and upgrading an upgraded collection gives exactly the same file
+        let again = dir.join("again.json");
+        postres::upgrade_postman_file(UpgradeConfig {
+            dest_file: again.to_string_lossy().to_string(),
+            source_file: upgraded.to_string_lossy().to_string(),
+            overwrite: OverwritePolicy::Overwrite,
+        })
+        .unwrap();
+        assert_eq!(fs::read_to_string(&again).unwrap(), content);
+    }
+}
+
+#[test]
+fn should_fail_on_broken_items_by_default() {
+    let dest = scratch_dir("broken_items_strict").join("out.http");
+    let res = postres::postman_file_to_http_file(config_for(
+        "broken_items.postman_collection.json",
+        &dest,
+    ));
+    assert!(matches!(
+        res,
+        Err(PostresError::PostManCollectionParsingError { .. })
+    ));
+    assert!(!dest.exists());
+}
+
+#[test]
+fn should_convert_everything_but_broken_items_when_lenient() {
+    let dest = scratch_dir("broken_items_lenient").join("out.http");
+    let report = postres::postman_file_to_http_file(Config {
+        parsing: ParsingMode::Lenient,
+        ..config_for("broken_items.postman_collection.json", &dest)
+    })
+    .unwrap();
+    assert_eq!(report.errors(), 1);
+    let broken = &report.entries[0].diagnostic.origin;
+    assert_eq!(broken.path, "Admin/delete user");
+    assert_eq!(broken.pointer, "/item/1/item/0");
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name list-users
+
+            GET http://127.0.0.1:3000/users http/1.1
+
+            #####
+            # @name Admin_get-user
+
+            GET http://127.0.0.1:3000/admin/users/1 http/1.1
+        "#}
+    );
+}
+
+#[test]
+fn should_fail_on_unknown_collection_schema() {
+    let dest = scratch_dir("unknown_schema").join("out.http");
+    let res = postres::postman_file_to_http_file(config_for(
+        "unknown_schema.postman_collection.json",
+        &dest,
+    ));
+    assert!(matches!(
+        res,
+        Err(PostresError::PostmanCollectionVersionNotSupportedError { .. })
+    ));
+    assert!(!dest.exists());
+}
+
+#[test]
+fn should_not_replace_existing_file_by_default() {
+    let dest = scratch_dir("no_clobber").join("out.http");
+    fs::write(&dest, "keep me").unwrap();
+    let res = postres::postman_file_to_http_file(config_for(
+        "string_requests.postman_collection.json",
+        &dest,
+    ));
+    assert!(matches!(
+        res,
+        Err(PostresError::DestFileAlreadyExistsError { .. })
+    ));
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "keep me");
+}
+
+#[test]
+fn should_replace_existing_file_when_overwrite_is_requested() {
+    let dest = scratch_dir("overwrite").join("out.http");
+    fs::write(&dest, "replace me").unwrap();
+    let mut config = config_for("string_requests.postman_collection.json", &dest);
+    config.overwrite = OverwritePolicy::Overwrite;
+    postres::postman_file_to_http_file(config).unwrap();
+    assert!(fs::read_to_string(&dest)
+        .unwrap()
+        .starts_with("#####\n# @name list-users"));
+}
+
+#[test]
+fn should_leave_nothing_behind_when_destination_cannot_be_written() {
+    let dir = scratch_dir("unwritable");
+    let dest = dir.join("missing-dir").join("out.http");
+    let res = postres::postman_file_to_http_file(config_for(
+        "string_requests.postman_collection.json",
+        &dest,
+    ));
+    assert!(matches!(res, Err(PostresError::DestFileError { .. })));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[test]
+fn should_fail_when_source_file_does_not_exist() {
+    let dest = scratch_dir("missing_source").join("out.http");
+    let res = postres::postman_file_to_http_file(config_for("does-not-exist.json", &dest));
+    assert!(matches!(res, Err(PostresError::SourceFileError { .. })));
+    assert!(!dest.exists());
+}
+
+#[test]
+fn should_merge_environments_into_vscode_settings() {
+    let dir = scratch_dir("environments");
+    let dest = dir.join("out.http");
+    let settings = dir.join(".vscode").join("settings.json");
+    fs::create_dir_all(settings.parent().unwrap()).unwrap();
+    fs::write(
+        &settings,
+        r#"{ "editor.tabSize": 2, "rest-client.environmentVariables": { "dev": { "user": "me" } } }"#,
+    )
+    .unwrap();
+    let report = postres::postman_file_to_http_file(Config {
+        environment_files: vec![
+            fixture("dev.postman_environment.json"),
+            fixture("prod.postman_environment.json"),
+        ],
+        ..config_for("string_requests.postman_collection.json", &dest)
+    })
+    .unwrap();
+    // one warning per secret which could not be exported
+    assert_eq!(report.warnings(), 2);
+    let settings: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&settings).unwrap()).unwrap();
+    assert_eq!(
+        settings,
+        serde_json::json!({
+            "editor.tabSize": 2,
+            "rest-client.environmentVariables": {
+                "$shared": { "apiVersion": "v1" },
+                "dev": {
+                    "user": "me",
+                    "baseUrl": "http://localhost:3000",
+                    "token": "<secret: value not exported>"
+                },
+                "prod": {
+                    "baseUrl": "https://api.example.com",
+                    "token": "<secret: value not exported>"
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn should_write_nothing_when_an_environment_file_is_invalid() {
+    let dir = scratch_dir("bad_environment");
+    let dest = dir.join("out.http");
+    let result = postres::postman_file_to_http_file(Config {
+        environment_files: vec![fixture("string_requests.postman_collection.json")],
+        ..config_for("string_requests.postman_collection.json", &dest)
+    });
+    assert!(matches!(
+        result,
+        Err(PostresError::PostmanEnvironmentParsingError { .. })
+    ));
+    assert!(!dest.exists());
+}
+
+#[test]
+fn should_audit_features_dropped_by_the_conversion() {
+    let audit = postres::audit_postman_file(AuditConfig {
+        source_file: fixture("dropped_features.postman_collection.json"),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(audit.requests, 3);
+    assert_eq!(audit.report.errors(), 0);
+    assert_eq!(
+        audit
+            .report
+            .dropped_by_feature()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![
+            (Feature::PreRequestScript, 1),
+            (Feature::TestScript, 1),
+            (Feature::Certificate, 1),
+            (Feature::Proxy, 1),
+            (Feature::ProtocolProfileBehavior, 1),
+            (Feature::HawkAuth, 2),
+            (Feature::SavedResponses, 1),
+        ]
+    );
+    // the collection script, both legacy requests and health
+    assert_eq!(audit.report.items_with_dropped_features(), 4);
+    let get_order = audit
+        .report
+        .dropped
+        .iter()
+        .find(|d| d.feature == Feature::Proxy)
+        .unwrap();
+    assert_eq!(get_order.origin.path, "Legacy/get order");
+    assert_eq!(get_order.origin.pointer, "/item/0/item/1");
+}
+
+#[test]
+fn should_split_requests_one_file_per_folder() {
+    let dir = scratch_dir("split_by_folder");
+    let split = dir.join("http");
+    let config = || Config {
+        environment_files: vec![fixture("dev.postman_environment.json")],
+        split_by_folder: Some(split.to_string_lossy().to_string()),
+        ..config_for(
+            "variables.postman_collection.json",
+            &dir.join("unused.http"),
+        )
+    };
+    postres::postman_file_to_http_file(config()).unwrap();
+    let root = fs::read_to_string(split.join("variables.http")).unwrap();
+    let users = fs::read_to_string(split.join("Users.http")).unwrap();
+    // every file declares the variables, so that it works on its own
+    assert!(root.starts_with("@baseUrl = http://127.0.0.1:3000\n"));
+    assert!(users.starts_with("@baseUrl = http://127.0.0.1:3000\n"));
+    assert!(root.contains("# @name get-order\n"));
+    assert!(!root.contains("# @name Users_get-user\n"));
+    assert!(users.contains("# @name Users_get-user\n"));
+    assert!(!dir.join("unused.http").exists());
+    assert!(split.join(".vscode").join("settings.json").exists());
+    // nothing is replaced without --overwrite
+    fs::remove_file(split.join("variables.http")).unwrap();
+    let res = postres::postman_file_to_http_file(config());
+    assert!(matches!(
+        res,
+        Err(PostresError::DestFileAlreadyExistsError { .. })
+    ));
+    assert!(!split.join("variables.http").exists());
+}
+
+#[test]
+fn should_only_chain_requests_written_to_the_same_file_when_splitting() {
+    let split = scratch_dir("split_chaining").join("http");
+    let report = postres::postman_file_to_http_file(Config {
+        split_by_folder: Some(split.to_string_lossy().to_string()),
+        ..config_for(
+            "chaining_folders.postman_collection.json",
+            &split.join("unused.http"),
+        )
+    })
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(split.join("Orders.http")).unwrap(),
+        indoc! {r#"
+            #####
+            # @name Orders_create-order
+
+            POST {{baseUrl}}/orders http/1.1
+            Authorization: Bearer {{token}}
+
+            #####
+            # @name Orders_get-order
+
+            GET {{baseUrl}}/orders/{{Orders_create-order.response.body.$.data.id}} http/1.1
+            Authorization: Bearer {{token}}
+        "#}
+    );
+    // each request of the folder reports the reference it lost
+    assert_eq!(report.warnings(), 2);
+    assert_eq!(
+        report.entries[0].diagnostic.origin.path,
+        "Orders/create order"
+    );
+    assert_eq!(
+        report.entries[0].message,
+        "{{token}} is set by the test script of request login, which is written to another file. RestClient only references requests of the same file, so it is left as it is"
+    );
+}
+
+#[test]
+fn should_name_requests_in_the_chosen_style_and_record_the_names() {
+    let dir = scratch_dir("naming");
+    let dest = dir.join("out.http");
+    let names_file = dir.join("names.json");
+    postres::postman_file_to_http_file(Config {
+        conversion: ConversionOptions {
+            naming: NamingStyle::Snake,
+            ..Default::default()
+        },
+        names_file: Some(names_file.to_string_lossy().to_string()),
+        ..config_for("v1.postman_collection.json", &dest)
+    })
+    .unwrap();
+    let http = fs::read_to_string(&dest).unwrap();
+    assert!(http.contains("# @name users_create_user\n"));
+    assert!(http.contains("# @name health\n"));
+    let names: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&names_file).unwrap()).unwrap();
+    assert_eq!(
+        names,
+        serde_json::json!([
+            {
+                "id": "2b6e9f1c-0000-4000-8000-000000000002",
+                "path": "Users/create user",
+                "name": "users_create_user"
+            },
+            {
+                "id": "2b6e9f1c-0000-4000-8000-000000000003",
+                "path": "health",
+                "name": "health"
+            }
+        ])
+    );
+}
+
+#[test]
+fn should_chain_requests_through_variables_set_by_test_scripts() {
+    let dest = scratch_dir("chaining").join("out.http");
+    let report =
+        postres::postman_file_to_http_file(config_for("chaining.postman_collection.json", &dest))
+            .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            @orderId = 
+
+            #####
+            # @name login
+
+            POST {{baseUrl}}/login http/1.1
+
+            #####
+            # @name create-order
+
+            POST {{baseUrl}}/orders http/1.1
+            Authorization: Bearer {{login.response.body.$.access_token}}
+
+            #####
+            # @name get-order
+
+            GET {{baseUrl}}/orders/{{create-order.response.body.$.data.id}} http/1.1
+            Authorization: Bearer {{login.response.body.$.access_token}}
+        "#}
+    );
+    assert_eq!(report.warnings(), 0);
+}
+
+#[test]
+fn should_write_multipart_bodies_with_text_and_file_parts() {
+    let dest = scratch_dir("form_data").join("out.http");
+    postres::postman_file_to_http_file(config_for("form_data.postman_collection.json", &dest))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name upload
+
+            POST {{baseUrl}}/upload http/1.1
+            Content-Type: multipart/form-data; boundary=----PostresFormBoundary7MA4YWxkTrZu0gW
+
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="title"
+
+            holidays
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="meta"
+            Content-Type: application/json
+
+            {"album": {{albumId}}}
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="cover"; filename="cover.jpg"
+            Content-Type: image/jpeg
+
+            < ./photos/cover.jpg
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="photos"; filename="1.jpg"
+
+            < ./photos/1.jpg
+            ------PostresFormBoundary7MA4YWxkTrZu0gW
+            Content-Disposition: form-data; name="photos"; filename="2.jpg"
+
+            < /home/me/2.jpg
+            ------PostresFormBoundary7MA4YWxkTrZu0gW--
+        "#}
+    );
+}
+
+#[test]
+fn should_reference_files_relative_to_the_http_file_and_copy_them_if_asked_to() {
+    let dir = scratch_dir("assets");
+    let dest = dir.join("out.http");
+    let report = postres::postman_file_to_http_file(Config {
+        files_base_dir: Some(fixture("assets")),
+        copy_assets: Some(dir.join("assets").to_string_lossy().to_string()),
+        ..config_for("assets.postman_collection.json", &dest)
+    })
+    .unwrap();
+    assert_eq!(report.warnings(), 1);
+    assert_eq!(report.entries[0].diagnostic.origin.path, "upload avatar");
+    assert!(report.entries[0]
+        .message
+        .starts_with("file missing.csv not found in"));
+    let http = fs::read_to_string(&dest).unwrap();
+    assert!(http.contains("\n< ./assets/user.json\n"));
+    assert!(http.contains("filename=\"avatar.png\"\n\n< ./assets/avatar.png\n"));
+    assert!(http.contains("filename=\"missing.csv\"\n\n< ./missing.csv\n"));
+    assert_eq!(
+        fs::read(dir.join("assets/avatar.png")).unwrap(),
+        fs::read(fixture("assets/avatar.png")).unwrap()
+    );
+
+    // without copies, the files are referenced where they were found
+    let dest = scratch_dir("assets_in_place").join("out.http");
+    postres::postman_file_to_http_file(Config {
+        files_base_dir: Some(fixture("assets")),
+        ..config_for("assets.postman_collection.json", &dest)
+    })
+    .unwrap();
+    let http = fs::read_to_string(&dest).unwrap();
+    let user = http.lines().find(|l| l.contains("user.json")).unwrap();
+    assert!(user.starts_with("< ../"));
+    assert!(dest
+        .parent()
+        .unwrap()
+        .join(user.trim_start_matches("< "))
+        .is_file());
+}
+
+#[test]
+fn should_keep_disabled_entries_as_comments_if_asked_to() {
+    let dest = scratch_dir("disabled").join("out.http");
+    postres::postman_file_to_http_file(Config {
+        conversion: ConversionOptions {
+            keep_disabled: true,
+            ..Default::default()
+        },
+        ..config_for("disabled.postman_collection.json", &dest)
+    })
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        indoc! {r#"
+            #####
+            # @name search
+
+            GET {{baseUrl}}/search
+                ?q=rust
+                # &page=2
+            Accept: application/json
+            # Accept: text/csv
+
+            #####
+            # @name login
+
+            POST {{baseUrl}}/login http/1.1
+            Content-Type: application/x-www-form-urlencoded
+            # &password=wrong
+
+            user={{user}}
+            &password={{password}}
+        "#}
+    );
+}
+
+#[test]
+fn should_write_nothing_if_the_destination_file_exists() {
+    let dir = scratch_dir("assets_no_clobber");
+    let dest = dir.join("out.http");
+    fs::write(&dest, "keep me").unwrap();
+    let res = postres::postman_file_to_http_file(Config {
+        files_base_dir: Some(fixture("assets")),
+        copy_assets: Some(dir.join("assets").to_string_lossy().to_string()),
+        names_file: Some(dir.join("names.json").to_string_lossy().to_string()),
+        ..config_for("assets.postman_collection.json", &dest)
+    });
+    assert!(matches!(
+        res,
+        Err(PostresError::DestFileAlreadyExistsError { .. })
+    ));
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "keep me");
+    assert!(!dir.join("assets").exists());
+    assert!(!dir.join("names.json").exists());
+}
+
+#[test]
+fn should_never_write_over_the_source_file() {
+    let dir = scratch_dir("source_is_dest");
+    let source = dir.join("users.json");
+    let collection =
+        fs::read_to_string(fixture("string_requests.postman_collection.json")).unwrap();
+    fs::write(&source, &collection).unwrap();
+    let res = postres::postman_file_to_http_file(Config {
+        dest_file: dir
+            .join(".")
+            .join("users.json")
+            .to_string_lossy()
+            .to_string(),
+        source_file: source.to_string_lossy().to_string(),
+        overwrite: OverwritePolicy::Overwrite,
+        ..Default::default()
+    });
+    assert!(matches!(
+        res,
+        Err(PostresError::DestFileIsSourceFileError { .. })
+    ));
+    let res = postres::upgrade_postman_file(UpgradeConfig {
+        dest_file: source.to_string_lossy().to_string(),
+        source_file: source.to_string_lossy().to_string(),
+        overwrite: OverwritePolicy::Overwrite,
+    });
+    assert!(matches!(
+        res,
+        Err(PostresError::DestFileIsSourceFileError { .. })
+    ));
+    assert_eq!(fs::read_to_string(&source).unwrap(), collection);
+}